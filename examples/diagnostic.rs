@@ -0,0 +1,34 @@
+use logger::{location::Section, Diagnostic, Location, Related};
+
+const SOURCE: &str = "let x = vec![\n    1,\n];\nlet x = vec![\n    3,\n];\n";
+
+enum CompileError {
+    Redefinition { name: &'static str },
+}
+
+impl Diagnostic for CompileError {
+    fn message(&self) -> String {
+        match self {
+            Self::Redefinition { name } => format!("`{name}` is defined multiple times"),
+        }
+    }
+
+    fn location(&self) -> Option<Location> {
+        Some(Location::from_text(SOURCE).section(Section::new(3..=5, 0..=2)))
+    }
+
+    fn hint(&self) -> Option<String> {
+        Some("Rename one of the bindings".to_string())
+    }
+
+    fn related(&self) -> Vec<Related> {
+        vec![Related {
+            location: Location::from_text(SOURCE).section(Section::new(0..=2, 0..=2)),
+            message: "previous definition is here".to_string(),
+        }]
+    }
+}
+
+fn main() {
+    CompileError::Redefinition { name: "x" }.output();
+}
@@ -0,0 +1,46 @@
+use logger::{error, warn, LogLevel, ResultExt};
+use std::{error::Error, fmt, fs, io};
+
+#[derive(Debug)]
+struct ConfigError(io::Error);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Failed to load config file")
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn read_config() -> Result<String, ConfigError> {
+    fs::read_to_string("config.toml").map_err(ConfigError)
+}
+
+fn read_port() -> Result<u16, Box<dyn Error>> {
+    Ok(read_config()?.trim().parse()?)
+}
+
+fn main() {
+    if let Err(err) = read_config() {
+        error!(err: err, hint: "Please make sure config.toml exists");
+    }
+
+    if let Err(err) = read_config() {
+        warn!(err: err, "Falling back to the default config");
+    }
+
+    let _ = read_config().log_err(LogLevel::Warning);
+
+    if let Err(err) = read_port() {
+        warn!(err: err, "Using the default port");
+    }
+    let _ = read_port().log_err(LogLevel::Warning);
+
+    read_config()
+        .with_hint("Please make sure config.toml exists")
+        .or_fatal("Cannot continue without a config");
+}
@@ -0,0 +1,16 @@
+use logger::{
+    file::{Naming, Retention, RollingFile, Rotation},
+    info, sink,
+};
+
+fn main() {
+    sink::add(
+        RollingFile::new("target/logs/example.log", Rotation::Size(256))
+            .naming(Naming::Index)
+            .retention(Retention::Files(3)),
+    );
+
+    for idx in 0..20 {
+        info!(hint: "This hint stays in the same file", "Message number {idx}");
+    }
+}
@@ -0,0 +1,27 @@
+use logger::{error, location::Section, Location};
+
+const TEMPLATE: &str = "<ul>
+  {% for user in users %}
+    <li>{{ user.nmae }}</li>
+  {% endfor %}
+</ul>";
+
+const GENERATED: &str = "fn render(users: &[User]) -> String {
+    let mut out = String::from(\"<ul>\");
+    for user in users {
+        write!(out, \"<li>{}</li>\", user.nmae);
+    }
+    out
+}";
+
+fn main() {
+    let template = Location::named("users.html", TEMPLATE).section(Section((1, 2)..=(2, 23)));
+    let generated = Location::named("users.html (generated)", GENERATED)
+        .section(Section((2, 4)..=(3, 44)))
+        .generated_from(template);
+
+    error!(location: generated, hint: "Did you mean `name`?", "No field `nmae` on type `User`");
+
+    let repl = Location::named("<repl:3>", "let x = 1 +\n    ;").section(Section((0, 10)..=(1, 4)));
+    error!(location: repl, "Expected an expression");
+}
@@ -0,0 +1,14 @@
+use logger::{error, group, info, warn};
+
+fn main() {
+    group("Building crate foo", || {
+        info!("Compiling foo v0.1.0");
+
+        group("Running build script", || {
+            warn!("Unused variable `x`");
+        });
+
+        let _tests = logger::group::start("Running tests");
+        error!("Test `parses_config` failed");
+    });
+}
@@ -2,9 +2,8 @@ use logger::{Colorize, Location, location::Section, warn};
 
 fn main() {
     logger::panic::set_panic_hook();
-    let location = Location::from_path("examples/highlighted/bad_code.rs")
-        .expect("Failed to read file")
-        .section(Section::new(1..=1, 20..=22));
+    let location =
+        Location::lazy("examples/highlighted/bad_code.rs").section(Section::new(1..=1, 20..=22));
 
     warn!(
         location: location,
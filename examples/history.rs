@@ -0,0 +1,14 @@
+use logger::{debug, fatal, history, info, level, trace, LogLevel};
+
+fn main() {
+    level::set_min_level(LogLevel::Info);
+    history::enable(4);
+
+    for idx in 0..6 {
+        trace!("Polling worker {idx}");
+    }
+    debug!("Connection pool exhausted");
+    info!("Retrying request");
+
+    fatal!("Request failed after every retry");
+}
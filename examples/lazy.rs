@@ -0,0 +1,19 @@
+use logger::{location::Section, warn, Location};
+use std::fs;
+
+fn main() {
+    let path = std::env::temp_dir().join("logger-example-lazy.rs");
+    fs::write(&path, "fn main() {\n    println!(\"Hello\\n\");\n}\n")
+        .expect("Failed to write file");
+
+    let location = || Location::lazy(&path).section(Section((1, 13)..=(2, 1)));
+
+    warn!(location: location(), "Read when rendered");
+
+    let changed = location();
+    fs::write(&path, "fn main() {}\n").expect("Failed to write file");
+    warn!(location: changed, "The file changed before rendering");
+
+    fs::remove_file(&path).expect("Failed to remove file");
+    warn!(location: location(), "The file is missing");
+}
@@ -1,7 +1,10 @@
 #[cfg(feature = "log")]
 fn main() {
-    logger::init().expect("Failed to set logger");
+    let config = "warn,log=debug".parse().expect("Invalid log directives");
+    logger::init_with(config).expect("Failed to set logger");
+
     log::info!("Hello, world!");
+    log::debug!(target: "other", "This is filtered out");
 }
 
 #[cfg(not(feature = "log"))]
@@ -0,0 +1,27 @@
+#[cfg(feature = "json")]
+fn main() {
+    use logger::{location::Section, lsp, make_warn, Location};
+    use std::path::Path;
+
+    let path = Path::new("examples/highlighted/bad_code.rs");
+    let location = Location::from_path(path)
+        .expect("Failed to read file")
+        .section(Section::new(1..=1, 20..=22));
+
+    let log = make_warn!(
+        location: location,
+        hint: "Remove the new line from the string",
+        "Using new line in println! call",
+    )
+    .code("W0001");
+
+    println!(
+        "{}",
+        lsp::publish_diagnostics(&lsp::file_uri(path), Some(1), &[log])
+    );
+}
+
+#[cfg(not(feature = "json"))]
+fn main() {
+    logger::error!("Enable the `json` feature to use this example");
+}
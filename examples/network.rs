@@ -0,0 +1,36 @@
+//! Ships logs to a local collector that only starts listening after the logs are written.
+#[cfg(feature = "json")]
+fn main() {
+    use logger::{error, info, network::Network, sink};
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    // Reserve a port, and close it so the first connection attempts fail.
+    let address = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to reserve a port");
+
+    let network = Network::tcp(address)
+        .expect("Failed to resolve address")
+        .capacity(2);
+    sink::set(vec![Box::new(network.clone())]);
+
+    info!("Buffered while the collector is down");
+    error!(hint: "Check the config", "Also buffered");
+    info!("Dropped, the buffer is full");
+    println!("Dropped {} logs", network.dropped());
+
+    let listener = TcpListener::bind(address).expect("Failed to bind collector");
+    let (stream, _) = listener.accept().expect("Failed to accept connection");
+
+    for line in BufReader::new(stream).lines().take(2) {
+        println!("{}", line.expect("Failed to read line"));
+    }
+}
+
+#[cfg(not(feature = "json"))]
+fn main() {
+    logger::error!("Enable the `json` feature to use this example");
+}
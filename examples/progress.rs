@@ -0,0 +1,24 @@
+use logger::{info, progress, warn};
+use std::{thread, time::Duration};
+
+fn main() {
+    let status = progress::status("Resolving dependencies");
+    let download = progress::bar(40, "Downloading");
+
+    for i in 1..=40 {
+        thread::sleep(Duration::from_millis(50));
+        download.inc(1);
+        status.tick();
+
+        if i % 10 == 0 {
+            info!("Downloaded crate {}", i / 10);
+        }
+        if i == 25 {
+            warn!("Retrying a failed download");
+        }
+    }
+
+    download.finish();
+    status.finish();
+    info!("Done");
+}
@@ -0,0 +1,18 @@
+use logger::{info_sampled, sink, warn_every, warn_once};
+use std::{thread, time::Duration};
+
+fn main() {
+    for i in 0..250 {
+        warn_once!("Config file not found, using defaults");
+        warn_every!(
+            Duration::from_millis(50),
+            "Queue is full, dropping item {i}"
+        );
+        info_sampled!(1 in 100, "Processed item {i}");
+
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Outputs the summaries of the bursts that ended without another log.
+    sink::flush();
+}
@@ -0,0 +1,25 @@
+//! Re-renders the diagnostics of a build, e.g.
+//! `cargo build --message-format=json | cargo run --example rustc`
+#[cfg(feature = "json")]
+fn main() {
+    use logger::rustc;
+    use std::io::{self, BufRead};
+
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("Failed to read stdin");
+
+        match rustc::parse(&line) {
+            Ok(Some(log)) => {
+                println!("{}", rustc::to_value(&log));
+                log.output();
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("{err}: {line}"),
+        }
+    }
+}
+
+#[cfg(not(feature = "json"))]
+fn main() {
+    logger::error!("Enable the `json` feature to use this example");
+}
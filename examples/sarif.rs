@@ -0,0 +1,26 @@
+#[cfg(feature = "json")]
+fn main() {
+    use logger::{location::Section, make_warn, sarif::Report, Location};
+
+    let location = Location::from_path("examples/highlighted/bad_code.rs")
+        .expect("Failed to read file")
+        .section(Section::new(1..=1, 20..=22));
+
+    let log = make_warn!(
+        location: location.clone(),
+        hint: "Remove the new line from the string",
+        "Using new line in println! call",
+    )
+    .code("W0001")
+    .suggestion(location, "", "Remove '\\n'");
+
+    let mut report = Report::new("bad-code-checker").version(env!("CARGO_PKG_VERSION"));
+    report.add(&log);
+
+    println!("{}", report.to_value());
+}
+
+#[cfg(not(feature = "json"))]
+fn main() {
+    logger::error!("Enable the `json` feature to use this example");
+}
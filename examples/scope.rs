@@ -0,0 +1,12 @@
+use logger::{info, scope, warn};
+
+fn parse(line: usize) {
+    let _scope = scope("parsing").field("line", line);
+    warn!("Unused variable `x`");
+}
+
+fn main() {
+    let _scope = scope("compiling foo.rs");
+    info!("Starting compilation");
+    parse(3);
+}
@@ -0,0 +1,43 @@
+//! Sends logs to stand-in journald and syslog listeners and prints what they receive.
+use logger::{
+    journald::Journald,
+    location::Section,
+    sink,
+    syslog::{Facility, Syslog},
+    warn, Location,
+};
+use std::{env, fs, net::UdpSocket, os::unix::net::UnixDatagram};
+
+fn main() {
+    let journal_path = env::temp_dir().join("logger-example-journal.socket");
+    let _ = fs::remove_file(&journal_path);
+    let journal = UnixDatagram::bind(&journal_path).expect("Failed to bind journal socket");
+    let syslog = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind syslog socket");
+
+    sink::set(vec![
+        Box::new(Journald::with_socket(&journal_path).expect("Failed to create journald sink")),
+        Box::new(
+            Syslog::udp(syslog.local_addr().unwrap())
+                .expect("Failed to create syslog sink")
+                .facility(Facility::Daemon),
+        ),
+    ]);
+
+    let location = Location::from_path("examples/highlighted/bad_code.rs")
+        .expect("Failed to read file")
+        .section(Section::new(1..=1, 20..=22));
+    warn!(location: location, hint: "Remove the new line\nfrom the string", "Using new line in println! call");
+
+    let mut buffer = [0; 4096];
+
+    let len = journal.recv(&mut buffer).unwrap();
+    println!(
+        "journald: {}",
+        String::from_utf8_lossy(&buffer[..len]).escape_debug()
+    );
+
+    let len = syslog.recv(&mut buffer).unwrap();
+    println!("syslog: {}", String::from_utf8_lossy(&buffer[..len]));
+
+    let _ = fs::remove_file(&journal_path);
+}
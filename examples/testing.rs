@@ -0,0 +1,20 @@
+use logger::{assert_logged, assert_not_logged, testing, warn};
+use std::thread;
+
+fn load_config() {
+    warn!(hint: "Using the default config", "Failed to read config file");
+}
+
+fn main() {
+    let capture = testing::init();
+
+    load_config();
+    thread::spawn(|| warn!("Logged from another thread"))
+        .join()
+        .unwrap();
+
+    assert_logged!(level = Warning, contains "config");
+    assert_not_logged!(level = Error);
+
+    println!("Captured {} logs", capture.logs().len());
+}
@@ -0,0 +1,15 @@
+#[cfg(feature = "tracing")]
+fn main() {
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+    tracing_subscriber::registry().with(logger::LogLayer).init();
+
+    let span = tracing::info_span!("compiling", file = "foo.rs");
+    let _enter = span.enter();
+    tracing::warn!(line = 3, "Unused variable `x`");
+}
+
+#[cfg(not(feature = "tracing"))]
+fn main() {
+    logger::error!("Enable the `tracing` feature to use this example");
+}
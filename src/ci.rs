@@ -0,0 +1,376 @@
+//! Detection of the CI system the process is running under, and annotations that surface
+//! logs with a [`Location`] inline in pull requests.
+
+#[cfg(feature = "json")]
+use crate::json::{self, Value};
+use crate::{location::Section, progress, sink::Sink, utils, Location, Log, LogLevel};
+use std::{env, path::Path};
+#[cfg(feature = "json")]
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+/// Conventional file name of a GitLab Code Quality report, as declared in the
+/// `artifacts:reports:codequality` of a job.
+#[cfg(feature = "json")]
+pub const CODE_QUALITY_REPORT: &str = "gl-code-quality-report.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    GitHubActions,
+    GitLab,
+    AzurePipelines,
+}
+
+impl Provider {
+    /// Detects the CI system from the environment variables it sets.
+    pub fn detect() -> Option<Self> {
+        let is_set =
+            |var: &str| env::var(var).is_ok_and(|value| value.eq_ignore_ascii_case("true"));
+
+        if is_set("GITHUB_ACTIONS") {
+            Some(Self::GitHubActions)
+        } else if is_set("GITLAB_CI") {
+            Some(Self::GitLab)
+        } else if is_set("TF_BUILD") {
+            Some(Self::AzurePipelines)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the annotation sink for this CI system. GitLab has none, since its annotations
+    /// come from a report file the job declares, see [`CodeQuality`].
+    pub fn sink(self) -> Option<Box<dyn Sink>> {
+        match self {
+            Self::GitHubActions | Self::AzurePipelines => Some(Box::new(Annotations::new(self))),
+            Self::GitLab => None,
+        }
+    }
+}
+
+/// 1-based, inclusive span of a location.
+struct Span {
+    path: String,
+    line: usize,
+    col: usize,
+    end_line: usize,
+    end_col: usize,
+}
+
+impl Span {
+    /// Span of the location of a log, unless it is only the place in the program that created
+    /// the log, as with logs from the `log` and `tracing` bridges.
+    fn of(log: &Log) -> Option<Self> {
+        let location = log.location.as_ref()?;
+
+        let is_call_site = log.caller.is_some_and(|caller| {
+            location.path.as_deref() == Some(Path::new(caller.file))
+                && location
+                    .section
+                    .as_ref()
+                    .is_some_and(|section| section.lines().start() + 1 == caller.line as usize)
+        });
+
+        if is_call_site {
+            None
+        } else {
+            Self::new(location)
+        }
+    }
+
+    fn new(location: &Location) -> Option<Self> {
+        let path = location.path.as_ref()?.display().to_string();
+        let section = location
+            .section
+            .clone()
+            .unwrap_or(Section::new(0..=0, 0..=0));
+
+        // Sections end at an exclusive column, an empty section on a single line still covers
+        // one column.
+        let end_col = if section.lines().start() == section.lines().end() {
+            (*section.cols().end()).max(section.cols().start() + 1)
+        } else {
+            *section.cols().end()
+        };
+
+        Some(Self {
+            path,
+            line: section.lines().start() + 1,
+            col: section.cols().start() + 1,
+            end_line: section.lines().end() + 1,
+            end_col,
+        })
+    }
+}
+
+/// Writes workflow commands that GitHub Actions and Azure Pipelines turn into annotations.
+#[derive(Debug, Clone, Copy)]
+pub struct Annotations {
+    provider: Provider,
+    min_level: LogLevel,
+}
+
+impl Annotations {
+    /// Annotates warnings and errors, since CI systems limit the amount of annotations.
+    pub fn new(provider: Provider) -> Self {
+        Self {
+            provider,
+            min_level: LogLevel::Warning,
+        }
+    }
+
+    /// Sets the lowest level that is annotated, GitHub Actions shows lower levels as notices.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Formats the workflow command for a log, if it has a location the CI system can show.
+    pub fn format(&self, log: &Log) -> Option<String> {
+        if log.level < self.min_level {
+            return None;
+        }
+
+        let span = Span::of(log)?;
+
+        match self.provider {
+            Provider::GitHubActions => {
+                let kind = match log.level {
+                    LogLevel::Trace | LogLevel::Debug => return None,
+                    LogLevel::Info | LogLevel::Note | LogLevel::Success => "notice",
+                    LogLevel::Warning => "warning",
+                    LogLevel::Error | LogLevel::Fatal => "error",
+                };
+
+                Some(format!(
+                    "::{kind} file={},line={},col={},endLine={},endColumn={}::{}",
+                    escape_github(&span.path, true),
+                    span.line,
+                    span.col,
+                    span.end_line,
+                    span.end_col,
+                    escape_github(&message(log), false),
+                ))
+            }
+            Provider::AzurePipelines => {
+                let kind = match log.level {
+                    LogLevel::Warning => "warning",
+                    LogLevel::Error | LogLevel::Fatal => "error",
+                    _ => return None,
+                };
+
+                Some(format!(
+                    "##vso[task.logissue type={kind};sourcepath={};linenumber={};columnnumber={};]{}",
+                    escape_azure(&span.path),
+                    span.line,
+                    span.col,
+                    escape_azure(&message(log)),
+                ))
+            }
+            Provider::GitLab => None,
+        }
+    }
+}
+
+impl Sink for Annotations {
+    fn write(&self, log: &Log) {
+        if let Some(command) = self.format(log) {
+            progress::suspend(|| println!("{command}"));
+        }
+    }
+}
+
+/// Collects logs with a location into a GitLab Code Quality report, such as
+/// `sink::add(CodeQuality::new(CODE_QUALITY_REPORT))`.
+///
+/// The report is written when the sinks are [flushed](crate::sink::flush), which
+/// [`fatal::exit`](crate::fatal::exit) does, so it has to be flushed before exiting otherwise.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub struct CodeQuality {
+    path: PathBuf,
+    issues: Mutex<Vec<Value>>,
+}
+
+#[cfg(feature = "json")]
+impl CodeQuality {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            issues: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Formats the Code Quality issue for a log, if it has a location.
+    pub fn format(log: &Log) -> Option<Value> {
+        let span = Span::of(log)?;
+
+        let severity = match log.level {
+            LogLevel::Fatal => "blocker",
+            LogLevel::Error => "critical",
+            LogLevel::Warning => "major",
+            LogLevel::Note => "minor",
+            _ => "info",
+        };
+
+        let position = |line: usize, column: usize| {
+            json::object([("line", line.into()), ("column", column.into())])
+        };
+
+        // Lines are left out, so an issue keeps its fingerprint when the code around it moves.
+        let check_name = check_name(log);
+        let fingerprint = fnv1a(&[&span.path, &check_name, &log.message]);
+
+        Some(json::object([
+            ("description", message(log).into()),
+            ("check_name", check_name.into()),
+            ("fingerprint", format!("{fingerprint:016x}").into()),
+            ("severity", severity.into()),
+            (
+                "location",
+                json::object([
+                    ("path", span.path.into()),
+                    (
+                        "positions",
+                        json::object([
+                            ("begin", position(span.line, span.col)),
+                            ("end", position(span.end_line, span.end_col)),
+                        ]),
+                    ),
+                ]),
+            ),
+        ]))
+    }
+}
+
+#[cfg(feature = "json")]
+impl Sink for CodeQuality {
+    fn write(&self, log: &Log) {
+        let Some(issue) = Self::format(log) else {
+            return;
+        };
+
+        self.issues
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(issue);
+    }
+
+    fn flush(&self) {
+        let issues = self.issues.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = fs::write(&self.path, Value::Array(issues.clone()).to_string());
+    }
+}
+
+/// Name of the check that produced a log: its target, the crate that created it, or the
+/// program.
+#[cfg(feature = "json")]
+fn check_name(log: &Log) -> String {
+    log.target
+        .clone()
+        .or_else(|| log.caller.map(|caller| caller.crate_name().to_string()))
+        .or_else(utils::app_name)
+        .unwrap_or_default()
+}
+
+/// 64-bit FNV-1a hash of `parts`, which unlike [`std::hash::DefaultHasher`] stays the same
+/// across Rust releases, as GitLab compares fingerprints between pipelines.
+#[cfg(feature = "json")]
+fn fnv1a(parts: &[&str]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.bytes().chain([0]))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+/// Message of a log including its hint, since annotations have no separate field for it.
+/// Colors are removed, as annotations are shown as plain text.
+fn message(log: &Log) -> String {
+    let message = match &log.hint {
+        Some(hint) => format!("{}\nhelp: {hint}", log.message),
+        None => log.message.to_string(),
+    };
+
+    utils::strip_ansi(&message)
+}
+
+fn escape_github(value: &str, property: bool) -> String {
+    let escaped = value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+
+    if property {
+        escaped.replace(':', "%3A").replace(',', "%2C")
+    } else {
+        escaped
+    }
+}
+
+fn escape_azure(value: &str) -> String {
+    value
+        .replace('%', "%AZP25")
+        .replace(';', "%3B")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+        .replace(']', "%5D")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Caller;
+
+    const CALLER: Caller = Caller {
+        module_path: "app::config",
+        file: "src/config.rs",
+        line: 12,
+    };
+
+    #[test]
+    fn annotates_warnings_outside_the_program() {
+        let annotations = Annotations::new(Provider::GitHubActions);
+        let location = Location::from_path_line("config.toml", 3);
+
+        let warning = Log::new(LogLevel::Warning, "unknown key").location(location.clone());
+        assert_eq!(
+            annotations.format(&warning).as_deref(),
+            Some("::warning file=config.toml,line=3,col=1,endLine=3,endColumn=1::unknown key"),
+        );
+
+        let info = Log::new(LogLevel::Info, "loaded").location(location);
+        assert_eq!(annotations.format(&info), None);
+        assert!(annotations
+            .min_level(LogLevel::Info)
+            .format(&info)
+            .is_some());
+
+        let bridged = Log::new(LogLevel::Warning, "retrying")
+            .location(Location::from_path_line(CALLER.file, 12))
+            .caller(CALLER);
+        assert_eq!(annotations.format(&bridged), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn fingerprints_ignore_the_line() {
+        let issue = |line| {
+            CodeQuality::format(
+                &Log::new(LogLevel::Error, "unknown key")
+                    .location(Location::from_path_line("config.toml", line))
+                    .caller(CALLER),
+            )
+            .expect("The log has a location")
+        };
+
+        assert_eq!(issue(3)["fingerprint"], issue(30)["fingerprint"]);
+        assert_eq!(fnv1a(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(&["a"]), fnv1a(&["a"]));
+        assert_ne!(fnv1a(&["ab", "c"]), fnv1a(&["a", "bc"]));
+    }
+}
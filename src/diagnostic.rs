@@ -0,0 +1,67 @@
+//! Trait for types that know how to describe themselves as a log.
+
+use crate::{Location, Log, LogLevel};
+
+/// A secondary location attached to a log, such as a previous definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Related {
+    pub location: Location,
+    pub message: String,
+}
+
+/// A replacement of the source at `location` that fixes a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub location: Location,
+    pub replacement: String,
+    pub message: String,
+}
+
+/// Implemented by error types that carry enough information to be rendered as a rich log.
+/// Every type implementing this trait can be converted into a [`Log`].
+pub trait Diagnostic {
+    fn message(&self) -> String;
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn location(&self) -> Option<Location> {
+        None
+    }
+
+    fn hint(&self) -> Option<String> {
+        None
+    }
+
+    /// Identifier of the kind of diagnostic, such as `E0308`.
+    fn code(&self) -> Option<String> {
+        None
+    }
+
+    fn related(&self) -> Vec<Related> {
+        Vec::new()
+    }
+
+    fn suggestions(&self) -> Vec<Suggestion> {
+        Vec::new()
+    }
+
+    /// Builds a log from this diagnostic and outputs it.
+    fn output(&self) {
+        Log::from(self).output();
+    }
+}
+
+impl<D: Diagnostic + ?Sized> From<&D> for Log {
+    fn from(diagnostic: &D) -> Self {
+        Self {
+            location: diagnostic.location(),
+            hint: diagnostic.hint(),
+            code: diagnostic.code(),
+            related: diagnostic.related(),
+            suggestions: diagnostic.suggestions(),
+            ..Log::new(diagnostic.level(), diagnostic.message())
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Conversion of [`std::error::Error`] chains into logs.
+
+use crate::{Log, LogLevel};
+use std::{error::Error, iter};
+
+/// Iterates over `err` followed by every error in its [`Error::source`] chain.
+pub fn chain(err: &dyn Error) -> impl Iterator<Item = &dyn Error> {
+    iter::successors(Some(err), |&err| err.source())
+}
+
+/// Borrows an error as a trait object, used by the `err:` argument of the log macros.
+///
+/// It is implemented for errors and error trait objects, and called with method syntax, so
+/// boxed errors, which don't implement [`Error`] themselves, are dereferenced to their error.
+pub trait AsError {
+    fn as_error(&self) -> &dyn Error;
+}
+
+impl<E: Error> AsError for E {
+    fn as_error(&self) -> &dyn Error {
+        self
+    }
+}
+
+impl AsError for dyn Error {
+    fn as_error(&self) -> &dyn Error {
+        self
+    }
+}
+
+impl AsError for dyn Error + Send + Sync {
+    fn as_error(&self) -> &dyn Error {
+        self
+    }
+}
+
+/// Creates a log at `level` from an error, used by the `err:` argument of the log macros and by
+/// [`ResultExt`](crate::ResultExt).
+pub fn log(level: LogLevel, err: &dyn Error) -> Log {
+    Log {
+        level,
+        ..Log::from(err)
+    }
+}
+
+impl From<&dyn Error> for Log {
+    /// Uses the error itself as the message and its sources as causes.
+    fn from(err: &dyn Error) -> Self {
+        let mut log = Log::new(LogLevel::Error, err.to_string());
+
+        if let Some(source) = err.source() {
+            log = log.caused_by(source);
+        }
+
+        log
+    }
+}
@@ -0,0 +1,102 @@
+//! Extension traits for logging errors in place of matching on them.
+
+use crate::{Log, LogLevel};
+use std::{borrow::Cow, error::Error};
+
+/// Conversion of an error value into a log, used by [`ResultExt`].
+pub trait IntoLog {
+    fn into_log(self) -> Log;
+}
+
+/// Implemented for errors and `Box<dyn Error>`. A `Box<dyn Error + Send + Sync>` is converted
+/// with `.map_err(|err| err as Box<dyn Error>)` first.
+impl<E: Into<Box<dyn Error>>> IntoLog for E {
+    fn into_log(self) -> Log {
+        crate::error::log(LogLevel::Error, &*self.into())
+    }
+}
+
+impl IntoLog for Box<Log> {
+    fn into_log(self) -> Log {
+        *self
+    }
+}
+
+pub trait ResultExt<T> {
+    /// Outputs the error at `level` and discards it.
+    fn log_err(self, level: LogLevel) -> Option<T>;
+
+    /// Outputs a fatal log with `message`, caused by the error, and exits the process with the
+    /// default [`fatal::exit_code`](crate::fatal::exit_code).
+    fn or_fatal(self, message: impl Into<Cow<'static, str>>) -> T;
+
+    /// Converts the error into a log with a hint attached.
+    /// # Errors
+    /// This function will return the converted error if `self` is an error.
+    fn with_hint(self, hint: impl Into<String>) -> Result<T, Box<Log>>;
+}
+
+impl<T, E: IntoLog> ResultExt<T> for Result<T, E> {
+    fn log_err(self, level: LogLevel) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(err) => {
+                Log {
+                    level,
+                    ..err.into_log()
+                }
+                .output();
+                None
+            }
+        }
+    }
+
+    fn or_fatal(self, message: impl Into<Cow<'static, str>>) -> T {
+        match self {
+            Ok(value) => value,
+            Err(err) => {
+                let log = err.into_log();
+                let mut causes = vec![log.message.into_owned()];
+                causes.extend(log.causes);
+
+                Log {
+                    level: LogLevel::Fatal,
+                    message: message.into(),
+                    causes,
+                    ..log
+                }
+                .exit()
+            }
+        }
+    }
+
+    fn with_hint(self, hint: impl Into<String>) -> Result<T, Box<Log>> {
+        self.map_err(|err| Box::new(err.into_log().hint(hint)))
+    }
+}
+
+pub trait OptionExt<T> {
+    /// Outputs `message` at `level` if there is no value.
+    fn log_none(self, level: LogLevel, message: impl Into<Cow<'static, str>>) -> Option<T>;
+
+    /// Outputs a fatal log with `message` and exits the process if there is no value.
+    fn or_fatal(self, message: impl Into<Cow<'static, str>>) -> T;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn log_none(self, level: LogLevel, message: impl Into<Cow<'static, str>>) -> Option<T> {
+        if self.is_none() {
+            Log::new(level, message).output();
+        }
+
+        self
+    }
+
+    fn or_fatal(self, message: impl Into<Cow<'static, str>>) -> T {
+        let Some(value) = self else {
+            Log::new(LogLevel::Fatal, message).exit()
+        };
+
+        value
+    }
+}
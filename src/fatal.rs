@@ -0,0 +1,37 @@
+//! Process termination after fatal logs.
+
+use crate::sink;
+use std::{
+    process,
+    sync::atomic::{AtomicBool, AtomicI32, Ordering},
+};
+
+static EXIT_CODE: AtomicI32 = AtomicI32::new(1);
+static ABORT: AtomicBool = AtomicBool::new(false);
+
+/// Sets the exit code used when a fatal log terminates the process without an explicit code.
+pub fn set_exit_code(code: i32) {
+    EXIT_CODE.store(code, Ordering::Relaxed);
+}
+
+/// Returns the exit code used when a fatal log terminates the process without an explicit code.
+pub fn exit_code() -> i32 {
+    EXIT_CODE.load(Ordering::Relaxed)
+}
+
+/// Makes fatal logs abort the process instead of exiting, which produces a core dump.
+pub fn set_abort_on_fatal(abort: bool) {
+    ABORT.store(abort, Ordering::Relaxed);
+}
+
+/// Flushes every sink and terminates the process with `code`,
+/// or aborts it if [`set_abort_on_fatal`] is enabled.
+pub fn exit(code: i32) -> ! {
+    sink::flush();
+
+    if ABORT.load(Ordering::Relaxed) {
+        process::abort();
+    }
+
+    process::exit(code);
+}
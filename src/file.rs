@@ -0,0 +1,472 @@
+//! File sink that rotates its file by size or daily, and removes old rotated files.
+//!
+//! Dates are in UTC.
+
+use crate::{sink::Sink, utils, Log};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// Rotates before a log would make the file larger than this many bytes.
+    Size(u64),
+    /// Rotates on the first log of a new day.
+    Daily,
+}
+
+/// How rotated files are named, after the name of the active file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Naming {
+    /// `app.log.1` is the most recent rotated file, older files are renamed to make room.
+    Index,
+    /// Named after the time the file was started, such as `app.log.2024-05-01` when rotating
+    /// daily, or `app.log.2024-05-01T13-45-10` when rotating by size.
+    Timestamp,
+}
+
+/// Which rotated files are kept, the active file is never removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+    All,
+    /// Keeps the most recent rotated files.
+    Files(usize),
+    /// Keeps the rotated files modified in the last days.
+    Days(u64),
+}
+
+/// Appends logs to a file without color, rotating it when it gets too large or old.
+///
+/// Every log is written with a single write, so a log is never split across two files.
+#[derive(Debug)]
+pub struct RollingFile {
+    path: PathBuf,
+    rotation: Rotation,
+    naming: Naming,
+    retention: Retention,
+    #[cfg(feature = "compression")]
+    compress: bool,
+    /// Opened on the first write, and closed when rotating or failing to write.
+    state: Mutex<Option<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    file: File,
+    size: u64,
+    started: SystemTime,
+}
+
+impl RollingFile {
+    pub fn new(path: impl Into<PathBuf>, rotation: Rotation) -> Self {
+        Self {
+            path: path.into(),
+            rotation,
+            naming: Naming::Index,
+            retention: Retention::All,
+            #[cfg(feature = "compression")]
+            compress: false,
+            state: Mutex::new(None),
+        }
+    }
+
+    pub fn naming(mut self, naming: Naming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    pub fn retention(mut self, retention: Retention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Compresses rotated files with gzip, adding a `.gz` extension.
+    #[cfg(feature = "compression")]
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    fn write_record(&self, state: &mut Option<State>, record: &[u8]) -> io::Result<()> {
+        let record_size = record.len() as u64;
+
+        let mut current = match state.take() {
+            Some(current) => current,
+            None => self.open()?,
+        };
+
+        let due = match self.rotation {
+            Rotation::Size(max) => current.size > 0 && current.size + record_size > max,
+            Rotation::Daily => days(current.started) != days(SystemTime::now()),
+        };
+
+        if due {
+            let started = current.started;
+            drop(current);
+            self.rotate(started)?;
+            current = self.open()?;
+        }
+
+        let current = state.insert(current);
+        current.file.write_all(record)?;
+        current.size += record_size;
+
+        Ok(())
+    }
+
+    fn open(&self) -> io::Result<State> {
+        if let Some(dir) = self.path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let metadata = file.metadata()?;
+
+        Ok(State {
+            size: metadata.len(),
+            // An existing file is considered started when it was last written to, so it is
+            // rotated on the first write of a new day after a restart.
+            started: if metadata.len() > 0 {
+                metadata.modified().unwrap_or_else(|_| SystemTime::now())
+            } else {
+                SystemTime::now()
+            },
+            file,
+        })
+    }
+
+    fn rotate(&self, started: SystemTime) -> io::Result<()> {
+        let target = match self.naming {
+            Naming::Index => {
+                for (path, _) in self.rotated_files()?.into_iter().rev() {
+                    let _ = fs::rename(&path, self.shifted(&path));
+                }
+
+                self.rotated_path("1")
+            }
+            Naming::Timestamp => {
+                let stamp = match self.rotation {
+                    Rotation::Daily => date(started),
+                    Rotation::Size(_) => timestamp(started),
+                };
+
+                let mut target = self.rotated_path(&stamp);
+                let mut n = 1;
+                while target.exists() || with_gz(&target).exists() {
+                    target = self.rotated_path(&format!("{stamp}-{n}"));
+                    n += 1;
+                }
+
+                target
+            }
+        };
+
+        fs::rename(&self.path, &target)?;
+
+        #[cfg(feature = "compression")]
+        if self.compress {
+            compress(&target)?;
+        }
+
+        self.remove_old()
+    }
+
+    fn remove_old(&self) -> io::Result<()> {
+        let rotated = self.rotated_files()?;
+
+        let expired = match self.retention {
+            Retention::All => return Ok(()),
+            Retention::Files(count) => rotated.into_iter().skip(count).collect::<Vec<_>>(),
+            Retention::Days(count) => {
+                let cutoff = SystemTime::now() - Duration::from_secs(count * DAY);
+
+                rotated
+                    .into_iter()
+                    .filter(|(path, _)| {
+                        fs::metadata(path)
+                            .and_then(|metadata| metadata.modified())
+                            .is_ok_and(|modified| modified < cutoff)
+                    })
+                    .collect()
+            }
+        };
+
+        for (path, _) in expired {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the rotated files with their suffix, most recent first.
+    fn rotated_files(&self) -> io::Result<Vec<(PathBuf, String)>> {
+        let prefix = format!("{}.", self.file_name());
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let mut rotated = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let suffix = name.strip_prefix(&prefix)?;
+                let suffix = suffix.strip_suffix(".gz").unwrap_or(suffix);
+
+                let valid = match self.naming {
+                    Naming::Index => suffix.parse::<usize>().is_ok(),
+                    Naming::Timestamp => suffix.starts_with(|c: char| c.is_ascii_digit()),
+                };
+
+                valid.then(|| (entry.path(), suffix.to_string()))
+            })
+            .collect::<Vec<_>>();
+
+        match self.naming {
+            Naming::Index => rotated.sort_by_key(|(_, suffix)| suffix.parse::<usize>().ok()),
+            Naming::Timestamp => rotated.sort_by(|(_, a), (_, b)| b.cmp(a)),
+        }
+
+        Ok(rotated)
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn rotated_path(&self, suffix: &str) -> PathBuf {
+        self.path
+            .with_file_name(format!("{}.{suffix}", self.file_name()))
+    }
+
+    /// Path of an index named file after increasing its index, keeping its extension.
+    fn shifted(&self, path: &Path) -> PathBuf {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let suffix = &name[self.file_name().len() + 1..];
+        let (index, extension) = suffix.split_once('.').unwrap_or((suffix, ""));
+        let index = index.parse::<usize>().unwrap_or_default() + 1;
+
+        if extension.is_empty() {
+            self.rotated_path(&index.to_string())
+        } else {
+            self.rotated_path(&format!("{index}.{extension}"))
+        }
+    }
+}
+
+impl Sink for RollingFile {
+    fn write(&self, log: &Log) {
+        let record = utils::strip_ansi(&log.to_string());
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if self.write_record(&mut state, record.as_bytes()).is_err() {
+            // Reopen the file on the next write, in case it was removed or the disk was full.
+            *state = None;
+        }
+    }
+
+    fn flush(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(state) = state.as_mut() {
+            let _ = state.file.flush();
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+fn compress(path: &Path) -> io::Result<()> {
+    use flate2::{write::GzEncoder, Compression};
+
+    let mut encoder = GzEncoder::new(File::create(with_gz(path))?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+
+    fs::remove_file(path)
+}
+
+fn with_gz(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".gz");
+    path.into()
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn days(time: SystemTime) -> u64 {
+    seconds(time) / DAY
+}
+
+fn date(time: SystemTime) -> String {
+    let (year, month, day) = utils::civil_date(days(time));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn timestamp(time: SystemTime) -> String {
+    let seconds = seconds(time) % DAY;
+
+    format!(
+        "{}T{:02}-{:02}-{:02}",
+        date(time),
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::{env, process};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("logger-file-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(file: &RollingFile, message: &'static str) {
+        file.write(&Log::new(LogLevel::Info, message));
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotates_by_size_with_index() {
+        let dir = temp_dir("index");
+        let path = dir.join("app.log");
+        let file = RollingFile::new(&path, Rotation::Size(1)).retention(Retention::Files(2));
+
+        for message in ["one", "two", "three", "four"] {
+            write(&file, message);
+        }
+        file.flush();
+
+        assert_eq!(names(&dir), ["app.log", "app.log.1", "app.log.2"]);
+        assert!(fs::read_to_string(&path).unwrap().contains("four"));
+        assert!(fs::read_to_string(dir.join("app.log.1"))
+            .unwrap()
+            .contains("three"));
+        assert!(fs::read_to_string(dir.join("app.log.2"))
+            .unwrap()
+            .contains("two"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_logs_below_the_size() {
+        let dir = temp_dir("size");
+        let path = dir.join("app.log");
+        let file = RollingFile::new(&path, Rotation::Size(1024));
+
+        write(&file, "one");
+        write(&file, "two");
+        file.flush();
+
+        assert_eq!(names(&dir), ["app.log"]);
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("one") && text.contains("two"));
+        assert!(!text.contains('\x1b'));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn timestamp_names_do_not_collide() {
+        let dir = temp_dir("timestamp");
+        let file = RollingFile::new(dir.join("app.log"), Rotation::Size(1))
+            .naming(Naming::Timestamp)
+            .retention(Retention::Files(1));
+
+        for message in ["one", "two", "three"] {
+            write(&file, message);
+        }
+
+        let names = names(&dir);
+        assert_eq!(names.len(), 2);
+        assert!(names[1].starts_with("app.log.") && names[1].ends_with("-1"));
+        assert!(fs::read_to_string(dir.join(&names[1]))
+            .unwrap()
+            .contains("two"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotates_a_file_from_a_previous_day() {
+        let dir = temp_dir("daily");
+        let path = dir.join("app.log");
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * DAY);
+
+        fs::write(&path, "old\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        let file = RollingFile::new(&path, Rotation::Daily).naming(Naming::Timestamp);
+        write(&file, "new");
+        file.flush();
+
+        let rotated = format!("app.log.{}", date(two_days_ago));
+        assert_eq!(names(&dir), ["app.log".to_string(), rotated.clone()]);
+        assert_eq!(fs::read_to_string(dir.join(rotated)).unwrap(), "old\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn removes_files_older_than_the_retention() {
+        let dir = temp_dir("days");
+        let path = dir.join("app.log");
+        let expired = dir.join("app.log.5");
+
+        fs::write(&expired, "expired\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&expired)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * DAY))
+            .unwrap();
+
+        let file = RollingFile::new(&path, Rotation::Size(1)).retention(Retention::Days(1));
+        write(&file, "one");
+        write(&file, "two");
+
+        assert_eq!(names(&dir), ["app.log", "app.log.1"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! Grouped sections of logs, indented under a header and closed with their elapsed time.
+//!
+//! Under GitHub Actions, GitLab CI and Azure Pipelines the groups are also wrapped in the markers that make
+//! them collapsible in the job log.
+
+use crate::{ci::Provider, level, progress, sink, stack::StackGuard, Log, LogLevel};
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+thread_local! {
+    static GROUPS: RefCell<Vec<Group>> = const { RefCell::new(Vec::new()) };
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct Group {
+    id: u64,
+    title: String,
+    started: Instant,
+    errors: usize,
+    failed: bool,
+    provider: Option<Provider>,
+}
+
+/// Ends its group when dropped.
+#[must_use = "the group is ended as soon as the guard is dropped"]
+pub struct GroupGuard(StackGuard<Group>);
+
+impl GroupGuard {
+    /// Marks the group as failed, even if no errors were logged inside of it.
+    pub fn fail(&self) {
+        self.0.with(|group| group.failed = true);
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        for group in self.0.pop().iter().rev() {
+            end(group);
+        }
+    }
+}
+
+/// Starts a group that lasts until the returned guard is dropped.
+pub fn start(title: impl Into<String>) -> GroupGuard {
+    let title = title.into();
+    let depth = depth();
+
+    // GitHub Actions doesn't support nested groups.
+    let provider =
+        Provider::detect().filter(|provider| *provider == Provider::GitLab || depth == 0);
+
+    let group = Group {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        title,
+        started: Instant::now(),
+        errors: 0,
+        failed: false,
+        provider,
+    };
+
+    match provider {
+        Some(Provider::GitHubActions) => marker(&format!("::group::{}", group.title)),
+        Some(Provider::AzurePipelines) => marker(&format!("##[group]{}", group.title)),
+        Some(Provider::GitLab) => marker(&format!(
+            "\x1b[0Ksection_start:{}:group_{}[collapsed=true]\r\x1b[0K{}",
+            unix_time(),
+            group.id,
+            group.title
+        )),
+        None => Log::new(LogLevel::Info, group.title.clone()).output(),
+    }
+
+    GroupGuard(StackGuard::push(&GROUPS, group))
+}
+
+/// Runs `f` inside of a group.
+pub fn group<T>(title: impl Into<String>, f: impl FnOnce() -> T) -> T {
+    let _group = start(title);
+    f()
+}
+
+/// Returns the amount of groups the current thread is in.
+pub fn depth() -> usize {
+    GROUPS.with_borrow(Vec::len)
+}
+
+/// Counts errors towards the status of the groups the current thread is in.
+pub(crate) fn record(level: LogLevel) {
+    if level >= LogLevel::Error {
+        GROUPS.with_borrow_mut(|groups| {
+            for group in groups {
+                group.errors += 1;
+            }
+        });
+    }
+}
+
+fn end(group: &Group) {
+    let elapsed = group.started.elapsed();
+
+    let summary = if group.failed || group.errors > 0 {
+        let errors = match group.errors {
+            0 => String::new(),
+            1 => " with 1 error".to_string(),
+            errors => format!(" with {errors} errors"),
+        };
+
+        GROUPS.with_borrow_mut(|groups| {
+            if let Some(parent) = groups.last_mut() {
+                parent.failed = true;
+            }
+        });
+
+        Log::new(
+            LogLevel::Error,
+            format!("{} failed{errors} after {elapsed:.2?}", group.title),
+        )
+    } else {
+        Log::new(
+            LogLevel::Success,
+            format!("{} finished in {elapsed:.2?}", group.title),
+        )
+    };
+
+    // The errors inside of the group were already counted by the parent groups,
+    // so the summary is written directly instead of going through `Log::output`.
+    if level::enabled(summary.level) {
+        sink::write(&summary);
+    }
+
+    match group.provider {
+        Some(Provider::GitHubActions) => marker("::endgroup::"),
+        Some(Provider::AzurePipelines) => marker("##[endgroup]"),
+        Some(Provider::GitLab) => marker(&format!(
+            "\x1b[0Ksection_end:{}:group_{}\r\x1b[0K",
+            unix_time(),
+            group.id
+        )),
+        None => {}
+    }
+}
+
+fn marker(line: &str) {
+    progress::suspend(|| println!("{line}"));
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
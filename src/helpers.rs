@@ -1,103 +1,187 @@
 macro_rules! define_log_helper {
-    ($name:ident, $make_name:ident, $level:ident) => {
+    (
+        $name:ident,
+        $make_name:ident,
+        $once_name:ident,
+        $every_name:ident,
+        $sampled_name:ident,
+        $level:ident
+    ) => {
         #[macro_export]
         macro_rules! $name {
-            (location: $location:expr, hint: $hint:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: Some($hint.to_string()),
-                }
-                .output();
-            }};
-            (hint: $hint:expr, location: $location:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: Some($hint.to_string()),
+            ($$($$arg:tt)+) => {{
+                if $crate::level::recorded($crate::level::LogLevel::$level) {
+                    $crate::$make_name!($$($$arg)+).output();
                 }
-                .output();
             }};
-            (location: $location:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: None,
+        }
+
+        /// Logs only the first time this call site is reached.
+        #[macro_export]
+        macro_rules! $once_name {
+            ($$($$arg:tt)+) => {{
+                static CALL_SITE: $crate::ratelimit::CallSite = $crate::ratelimit::CallSite::new(
+                    $crate::level::LogLevel::$level,
+                    $crate::Caller { module_path: module_path!(), file: file!(), line: line!() },
+                );
+
+                if $crate::level::recorded($crate::level::LogLevel::$level) && CALL_SITE.once() {
+                    $crate::$make_name!($$($$arg)+).output();
                 }
-                .output();
             }};
-            (hint: $hint:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: None,
-                    hint: Some($hint.to_string()),
+        }
+
+        /// Logs at most once per period for this call site, e.g. `warn_every!(Duration::from_secs(5), ...)`.
+        #[macro_export]
+        macro_rules! $every_name {
+            ($$period:expr, $$($$arg:tt)+) => {{
+                static CALL_SITE: $crate::ratelimit::CallSite = $crate::ratelimit::CallSite::new(
+                    $crate::level::LogLevel::$level,
+                    $crate::Caller { module_path: module_path!(), file: file!(), line: line!() },
+                );
+
+                if $crate::level::recorded($crate::level::LogLevel::$level)
+                    && let Some(suppressed) = CALL_SITE.every($$period)
+                {
+                    CALL_SITE.summarize(suppressed);
+                    $crate::$make_name!($$($$arg)+).output();
                 }
-                .output();
             }};
-            ($$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: None,
-                    hint: None,
+        }
+
+        /// Logs one in every `n` calls of this call site, e.g. `info_sampled!(1 in 100, ...)`.
+        #[macro_export]
+        macro_rules! $sampled_name {
+            (1 in $$n:expr, $$($$arg:tt)+) => {{
+                static CALL_SITE: $crate::ratelimit::CallSite = $crate::ratelimit::CallSite::new(
+                    $crate::level::LogLevel::$level,
+                    $crate::Caller { module_path: module_path!(), file: file!(), line: line!() },
+                );
+
+                if $crate::level::recorded($crate::level::LogLevel::$level)
+                    && let Some(suppressed) = CALL_SITE.sampled($$n)
+                {
+                    CALL_SITE.summarize(suppressed);
+                    $crate::$make_name!($$($$arg)+).output();
                 }
-                .output();
             }};
         }
 
         #[macro_export]
         macro_rules! $make_name {
-            (location: $location:expr, hint: $hint:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: Some($hint.to_string()),
-                }
+            (@log err: $err:expr, hint: $hint:expr $$(,)?) => {{
+                $crate::error::log(
+                    $crate::level::LogLevel::$level,
+                    { use $crate::error::AsError as _; (&$err).as_error() },
+                )
+                .hint($hint.to_string())
             }};
-            (hint: $hint:expr, location: $location:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: Some($hint.to_string()),
-                }
+            (@log err: $err:expr $$(,)?) => {{
+                $crate::error::log(
+                    $crate::level::LogLevel::$level,
+                    { use $crate::error::AsError as _; (&$err).as_error() },
+                )
             }};
-            (location: $location:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: Some($location),
-                    hint: None,
-                }
+            (@log err: $err:expr, hint: $hint:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .caused_by({ use $crate::error::AsError as _; (&$err).as_error() })
+                    .hint($hint.to_string())
             }};
-            (hint: $hint:expr, $$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: None,
-                    hint: Some($hint.to_string()),
-                }
+            (@log err: $err:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .caused_by({ use $crate::error::AsError as _; (&$err).as_error() })
+            }};
+            (@log location: $location:expr, hint: $hint:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .location($location)
+                    .hint($hint.to_string())
+            }};
+            (@log hint: $hint:expr, location: $location:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .location($location)
+                    .hint($hint.to_string())
+            }};
+            (@log location: $location:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .location($location)
+            }};
+            (@log hint: $hint:expr, $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
+                    .hint($hint.to_string())
+            }};
+            (@log $$($$arg:tt)+) => {{
+                $crate::Log::new($crate::level::LogLevel::$level, $crate::utils::message(format_args!($$($$arg)*)))
             }};
             ($$($$arg:tt)+) => {{
-                $crate::Log {
-                    level: $crate::level::LogLevel::$level,
-                    message: format!($$($$arg)*),
-                    location: None,
-                    hint: None,
-                }
+                $crate::$make_name!(@log $$($$arg)+).caller($crate::Caller {
+                    module_path: module_path!(),
+                    file: file!(),
+                    line: line!(),
+                })
             }};
         }
     };
 }
 
-define_log_helper!(trace, make_trace, Trace);
-define_log_helper!(debug, make_debug, Debug);
-define_log_helper!(info, make_info, Info);
-define_log_helper!(warn, make_warn, Warning);
-define_log_helper!(error, make_error, Error);
-define_log_helper!(fatal, make_fatal, Fatal);
+define_log_helper!(
+    trace,
+    make_trace,
+    trace_once,
+    trace_every,
+    trace_sampled,
+    Trace
+);
+define_log_helper!(
+    debug,
+    make_debug,
+    debug_once,
+    debug_every,
+    debug_sampled,
+    Debug
+);
+define_log_helper!(info, make_info, info_once, info_every, info_sampled, Info);
+define_log_helper!(note, make_note, note_once, note_every, note_sampled, Note);
+define_log_helper!(
+    success,
+    make_success,
+    success_once,
+    success_every,
+    success_sampled,
+    Success
+);
+define_log_helper!(
+    warn,
+    make_warn,
+    warn_once,
+    warn_every,
+    warn_sampled,
+    Warning
+);
+define_log_helper!(
+    error,
+    make_error,
+    error_once,
+    error_every,
+    error_sampled,
+    Error
+);
+define_log_helper!(
+    fatal,
+    make_fatal,
+    fatal_once,
+    fatal_every,
+    fatal_sampled,
+    Fatal
+);
+
+/// Like [`fatal!`], but terminates the process afterwards with the default
+/// [`fatal::exit_code`](crate::fatal::exit_code), or the one given with `code:`.
+#[macro_export]
+macro_rules! fatal_exit {
+    (code: $code:expr, $($arg:tt)+) => {
+        $crate::make_fatal!($($arg)+).exit_with($code)
+    };
+    ($($arg:tt)+) => {
+        $crate::make_fatal!($($arg)+).exit()
+    };
+}
@@ -0,0 +1,94 @@
+//! Ring buffer of the most recent logs, kept regardless of the runtime level filter.
+//!
+//! The history is dumped to stderr before a fatal log is output, which includes panics caught
+//! by [`set_panic_hook`](crate::panic::set_panic_hook), so crash reports show what led up to
+//! them. Levels compiled out with the `max_level_*` features are never kept.
+
+use crate::{progress, Log, LogLevel};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static LOGS: Mutex<VecDeque<Log>> = Mutex::new(VecDeque::new());
+
+fn logs_mut() -> MutexGuard<'static, VecDeque<Log>> {
+    LOGS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Keeps the last `capacity` logs of every level, `0` disables the history.
+pub fn enable(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+
+    let mut logs = logs_mut();
+    let excess = logs.len().saturating_sub(capacity);
+    logs.drain(..excess);
+}
+
+/// Stops keeping logs and clears the history.
+pub fn disable() {
+    enable(0);
+}
+
+/// Amount of logs kept, `0` when the history is disabled.
+pub fn capacity() -> usize {
+    CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Returns the kept logs, oldest first.
+pub fn logs() -> Vec<Log> {
+    logs_mut().iter().cloned().collect()
+}
+
+pub fn clear() {
+    logs_mut().clear();
+}
+
+pub(crate) fn record(log: Log) {
+    let capacity = capacity();
+    if capacity == 0 {
+        return;
+    }
+
+    let mut logs = logs_mut();
+    while logs.len() >= capacity {
+        logs.pop_front();
+    }
+    logs.push_back(log);
+}
+
+/// Writes the kept logs, oldest first, below a header.
+///
+/// # Errors
+/// This function will propagate errors from writing to `writer`.
+pub fn dump_to(writer: &mut impl Write) -> io::Result<()> {
+    // Copied so the history is not locked while the logs are formatted and written.
+    let logs = logs();
+    if logs.is_empty() {
+        return Ok(());
+    }
+
+    let header = Log::new(
+        LogLevel::Note,
+        format!("last {} logs, including hidden ones:", logs.len()),
+    );
+    write!(writer, "{header}")?;
+
+    for log in &logs {
+        write!(writer, "{log}")?;
+    }
+
+    writer.flush()
+}
+
+/// Writes the kept logs to stderr, see [`dump_to`].
+pub fn dump() {
+    progress::suspend(|| {
+        let _ = dump_to(&mut io::stderr().lock());
+    });
+}
@@ -0,0 +1,144 @@
+//! Sink sending logs to the systemd journal with its native protocol.
+
+use crate::{sink::Sink, syslog, Log};
+use std::{env, io, os::unix::net::UnixDatagram, path::PathBuf};
+
+/// Socket the journal receives native messages on.
+pub const DEFAULT_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Sends every log to the journal as structured fields.
+///
+/// Besides the standard `MESSAGE`, `PRIORITY`, `SYSLOG_IDENTIFIER`, `CODE_FILE` and `CODE_LINE`
+/// fields, logs have `HINT`, `DIAGNOSTIC_CODE`, `TARGET` and a `CAUSE` field per cause.
+/// Logs that do not fit in a single datagram are dropped.
+#[derive(Debug)]
+pub struct Journald {
+    socket: UnixDatagram,
+    path: PathBuf,
+}
+
+impl Journald {
+    /// Sends logs to the journal at [`DEFAULT_SOCKET`].
+    ///
+    /// # Errors
+    /// This function will propagate errors from creating the socket.
+    pub fn new() -> io::Result<Self> {
+        Self::with_socket(DEFAULT_SOCKET)
+    }
+
+    /// # Errors
+    /// This function will propagate errors from creating the socket.
+    pub fn with_socket(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path: path.into(),
+        })
+    }
+
+    /// Formats the native protocol message for a log.
+    pub fn format(log: &Log) -> Vec<u8> {
+        let mut message = Vec::new();
+
+        field(&mut message, "MESSAGE", &log.message);
+        field(
+            &mut message,
+            "PRIORITY",
+            &syslog::severity(log.level).to_string(),
+        );
+
+        if let Ok(app_name) = env::var("LOGGER_APP_NAME") {
+            field(&mut message, "SYSLOG_IDENTIFIER", &app_name);
+        }
+
+        if let Some(location) = &log.location
+            && let Some(path) = &location.path
+        {
+            field(&mut message, "CODE_FILE", &path.display().to_string());
+
+            if let Some(section) = &location.section {
+                field(
+                    &mut message,
+                    "CODE_LINE",
+                    &(section.lines().start() + 1).to_string(),
+                );
+            }
+        }
+
+        if let Some(hint) = &log.hint {
+            field(&mut message, "HINT", hint);
+        }
+        if let Some(code) = &log.code {
+            field(&mut message, "DIAGNOSTIC_CODE", code);
+        }
+        if let Some(target) = &log.target {
+            field(&mut message, "TARGET", target);
+        }
+        for cause in &log.causes {
+            field(&mut message, "CAUSE", cause);
+        }
+
+        message
+    }
+}
+
+impl Sink for Journald {
+    fn write(&self, log: &Log) {
+        let _ = self.socket.send_to(&Self::format(log), &self.path);
+    }
+}
+
+/// Appends a field, values with a new line are prefixed with their length instead of `=`.
+fn field(message: &mut Vec<u8>, name: &str, value: &str) {
+    message.extend_from_slice(name.as_bytes());
+
+    if value.contains('\n') {
+        message.push(b'\n');
+        message.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        message.push(b'=');
+    }
+
+    message.extend_from_slice(value.as_bytes());
+    message.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::{fs, process, time::Duration};
+
+    #[test]
+    fn sends_native_fields() {
+        let path = env::temp_dir().join(format!("logger-journald-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let listener = UnixDatagram::bind(&path).unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let log = Log::new(LogLevel::Warning, "disk almost full")
+            .hint("remove old files")
+            .code("W01")
+            .target("storage");
+        let log = Log {
+            causes: vec!["quota exceeded".to_string(), "two\nlines".to_string()],
+            ..log
+        };
+        Journald::with_socket(&path).unwrap().write(&log);
+
+        let mut buffer = [0; 4096];
+        let len = listener.recv(&mut buffer).unwrap();
+        let message = &buffer[..len];
+        fs::remove_file(&path).unwrap();
+
+        assert!(message.starts_with(b"MESSAGE=disk almost full\nPRIORITY=4\n"));
+
+        let mut fields = b"HINT=remove old files\nDIAGNOSTIC_CODE=W01\nTARGET=storage\n\
+            CAUSE=quota exceeded\nCAUSE\n"
+            .to_vec();
+        fields.extend_from_slice(&9u64.to_le_bytes());
+        fields.extend_from_slice(b"two\nlines\n");
+        assert!(message.ends_with(&fields));
+    }
+}
@@ -0,0 +1,164 @@
+//! JSON encoding of logs, shared by the machine readable input and output formats.
+
+use crate::{Location, Log};
+pub use serde_json::{Error, Value};
+
+/// Creates an object, skipping fields that are [`Value::Null`].
+pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Value)>) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| (key.into(), value))
+            .collect(),
+    )
+}
+
+/// JSON object with every field of a log, as shipped by the [`network`](crate::network) sinks.
+impl From<&Log> for Value {
+    fn from(log: &Log) -> Self {
+        let strings = |values: &[String]| {
+            Value::Array(values.iter().map(|value| value.as_str().into()).collect())
+        };
+
+        object([
+            ("level", log.level.to_string().into()),
+            ("message", log.message.as_ref().into()),
+            ("code", log.code.clone().into()),
+            ("target", log.target.clone().into()),
+            ("location", log.location.as_ref().map(location).into()),
+            ("label", log.label.clone().into()),
+            ("hint", log.hint.clone().into()),
+            ("causes", strings(&log.causes)),
+            (
+                "scopes",
+                Value::Array(
+                    log.scopes
+                        .iter()
+                        .map(|scope| {
+                            object([
+                                ("name", scope.name.as_str().into()),
+                                (
+                                    "fields",
+                                    object(
+                                        scope.fields.iter().map(|(key, value)| {
+                                            (key.as_str(), value.as_str().into())
+                                        }),
+                                    ),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "related",
+                Value::Array(
+                    log.related
+                        .iter()
+                        .map(|related| {
+                            object([
+                                ("location", location(&related.location)),
+                                ("message", related.message.as_str().into()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "suggestions",
+                Value::Array(
+                    log.suggestions
+                        .iter()
+                        .map(|suggestion| {
+                            object([
+                                ("location", location(&suggestion.location)),
+                                ("replacement", suggestion.replacement.as_str().into()),
+                                ("message", suggestion.message.as_str().into()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+/// 1-based position of a location, with an exclusive end column.
+fn location(location: &Location) -> Value {
+    let section = location.section.as_ref();
+
+    object([
+        (
+            "path",
+            location
+                .path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .into(),
+        ),
+        ("name", location.name.clone().into()),
+        (
+            "line",
+            section.map(|section| section.lines().start() + 1).into(),
+        ),
+        (
+            "column",
+            section.map(|section| section.cols().start() + 1).into(),
+        ),
+        (
+            "end_line",
+            section.map(|section| section.lines().end() + 1).into(),
+        ),
+        (
+            "end_column",
+            section.map(|section| section.cols().end() + 1).into(),
+        ),
+        (
+            "generated_from",
+            location
+                .generated_from
+                .as_deref()
+                .map(self::location)
+                .into(),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{location::Section, LogLevel};
+
+    #[test]
+    fn skips_missing_fields() {
+        let log = Log::new(LogLevel::Warning, "unused variable")
+            .location(Location::from_path_line("src/main.rs", 3))
+            .hint("remove it");
+
+        let value = Value::from(&log);
+
+        assert_eq!(value["level"], "warning");
+        assert_eq!(value["message"], "unused variable");
+        assert_eq!(value["hint"], "remove it");
+        assert_eq!(value["location"]["path"], "src/main.rs");
+        assert_eq!(value["location"]["line"], 3);
+        assert!(value.get("code").is_none());
+        assert!(value["location"].get("generated_from").is_none());
+    }
+
+    #[test]
+    fn round_trips() {
+        let log = Log::new(LogLevel::Error, "quote \" and \u{1}").related(
+            Location::named("<repl:1>", "é").section(Section::new(0..=0, 0..=2)),
+            "défini ici",
+        );
+
+        let value = Value::from(&log);
+        let parsed: Value = value.to_string().parse().unwrap();
+
+        assert_eq!(parsed, value);
+        assert_eq!(parsed["related"][0]["message"], "défini ici");
+        assert_eq!(parsed["related"][0]["location"]["end_column"], 3);
+    }
+}
@@ -1,23 +1,51 @@
-use std::fmt::Display;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
 
 use colored::Color;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Levels are ordered by severity, from [`LogLevel::Trace`] to [`LogLevel::Fatal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LogLevel {
     Trace,
     Debug,
     Info,
+    Note,
+    Success,
     Warning,
     Error,
     Fatal,
 }
 
+impl LogLevel {
+    const ALL: [LogLevel; 8] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Note,
+        LogLevel::Success,
+        LogLevel::Warning,
+        LogLevel::Error,
+        LogLevel::Fatal,
+    ];
+
+    /// Iterates over every level, from least to most severe.
+    pub fn all() -> impl DoubleEndedIterator<Item = LogLevel> {
+        Self::ALL.into_iter()
+    }
+}
+
 impl From<LogLevel> for Color {
     fn from(val: LogLevel) -> Self {
         match val {
             LogLevel::Trace => Color::Magenta,
             LogLevel::Debug => Color::Green,
             LogLevel::Info => Color::Blue,
+            LogLevel::Note => Color::Cyan,
+            LogLevel::Success => Color::BrightGreen,
             LogLevel::Warning => Color::Yellow,
             LogLevel::Error => Color::BrightRed,
             LogLevel::Fatal => Color::Red,
@@ -34,6 +62,8 @@ impl Display for LogLevel {
                 LogLevel::Trace => "trace",
                 LogLevel::Debug => "debug",
                 LogLevel::Info => "info",
+                LogLevel::Note => "note",
+                LogLevel::Success => "success",
                 LogLevel::Warning => "warning",
                 LogLevel::Error => "error",
                 LogLevel::Fatal => "fatal",
@@ -41,3 +71,145 @@ impl Display for LogLevel {
         )
     }
 }
+
+/// Error returned when parsing an unknown level name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(String);
+
+impl Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown log level `{}`", self.0)
+    }
+}
+
+impl Error for ParseLevelError {}
+
+/// Parses a level name case-insensitively, also accepting common abbreviations like `warn`.
+impl FromStr for LogLevel {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "note" => Ok(LogLevel::Note),
+            "success" => Ok(LogLevel::Success),
+            "warn" | "warning" => Ok(LogLevel::Warning),
+            "error" | "err" => Ok(LogLevel::Error),
+            "fatal" => Ok(LogLevel::Fatal),
+            _ => Err(ParseLevelError(s.to_string())),
+        }
+    }
+}
+
+/// Least severe level that is compiled in, as selected by the `max_level_*` and
+/// `release_max_level_*` features. `None` means every level is compiled out.
+pub const STATIC_MIN_LEVEL: Option<LogLevel> = static_min_level();
+
+const fn static_min_level() -> Option<LogLevel> {
+    if cfg!(not(debug_assertions)) {
+        if cfg!(feature = "release_max_level_off") {
+            return None;
+        } else if cfg!(feature = "release_max_level_error") {
+            return Some(LogLevel::Error);
+        } else if cfg!(feature = "release_max_level_warn") {
+            return Some(LogLevel::Warning);
+        } else if cfg!(feature = "release_max_level_info") {
+            return Some(LogLevel::Info);
+        } else if cfg!(feature = "release_max_level_debug") {
+            return Some(LogLevel::Debug);
+        } else if cfg!(feature = "release_max_level_trace") {
+            return Some(LogLevel::Trace);
+        }
+    }
+
+    if cfg!(feature = "max_level_off") {
+        None
+    } else if cfg!(feature = "max_level_error") {
+        Some(LogLevel::Error)
+    } else if cfg!(feature = "max_level_warn") {
+        Some(LogLevel::Warning)
+    } else if cfg!(feature = "max_level_info") {
+        Some(LogLevel::Info)
+    } else if cfg!(feature = "max_level_debug") {
+        Some(LogLevel::Debug)
+    } else {
+        Some(LogLevel::Trace)
+    }
+}
+
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Trace as u8);
+
+/// Sets the least severe level that is output at runtime.
+pub fn set_min_level(level: LogLevel) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns the least severe level that is output at runtime.
+pub fn min_level() -> LogLevel {
+    LogLevel::ALL
+        .get(MIN_LEVEL.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or(LogLevel::Fatal)
+}
+
+/// Whether logs at `level` are output.
+#[inline]
+pub fn enabled(level: LogLevel) -> bool {
+    match STATIC_MIN_LEVEL {
+        Some(static_min_level) => level >= static_min_level && level >= min_level(),
+        None => false,
+    }
+}
+
+/// Whether logs at `level` are output or kept in the [`history`](crate::history), this is
+/// checked by the log macros before formatting their message.
+#[inline]
+pub fn recorded(level: LogLevel) -> bool {
+    enabled(level)
+        || STATIC_MIN_LEVEL.is_some_and(|static_min_level| level >= static_min_level)
+            && crate::history::capacity() > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_abbreviations() {
+        for (name, level) in [
+            ("warn", LogLevel::Warning),
+            ("WARNING", LogLevel::Warning),
+            (" Warn ", LogLevel::Warning),
+            ("err", LogLevel::Error),
+            ("ERROR", LogLevel::Error),
+            ("success", LogLevel::Success),
+        ] {
+            assert_eq!(name.parse(), Ok(level), "{name:?}");
+        }
+
+        assert_eq!(
+            "loud".parse::<LogLevel>(),
+            Err(ParseLevelError("loud".to_string()))
+        );
+        assert!("".parse::<LogLevel>().is_err());
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for level in LogLevel::all() {
+            assert_eq!(level.to_string().parse(), Ok(level));
+        }
+    }
+
+    #[test]
+    fn orders_by_severity() {
+        let levels = LogLevel::all().collect::<Vec<_>>();
+
+        assert!(levels.is_sorted());
+        assert_eq!(levels.first(), Some(&LogLevel::Trace));
+        assert_eq!(levels.last(), Some(&LogLevel::Fatal));
+        assert!(LogLevel::Success < LogLevel::Warning);
+    }
+}
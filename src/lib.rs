@@ -1,43 +1,121 @@
 #![feature(let_chains, macro_metavar_expr)]
 
+pub mod ci;
+pub mod diagnostic;
+pub mod error;
+pub mod ext;
+pub mod fatal;
+pub mod file;
+pub mod group;
 pub mod helpers;
+pub mod history;
+#[cfg(unix)]
+pub mod journald;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod level;
 pub mod location;
+#[cfg(feature = "json")]
+pub mod lsp;
+#[cfg(feature = "json")]
+pub mod network;
 pub mod panic;
+pub mod progress;
+pub mod ratelimit;
+#[cfg(feature = "json")]
+pub mod rustc;
+#[cfg(feature = "json")]
+pub mod sarif;
+pub mod scope;
+pub mod sink;
+mod stack;
+pub mod syslog;
+pub mod testing;
 pub mod utils;
 
 #[cfg(feature = "log")]
 mod log_impl;
+#[cfg(feature = "tracing")]
+mod tracing_impl;
 use location::Section;
 #[cfg(feature = "log")]
-pub use log_impl::{init, Logger};
+pub use log_impl::{init, init_with, Config, LogFacade, Logger};
+#[cfg(feature = "tracing")]
+pub use tracing_impl::LogLayer;
 
 pub use colored::{self, Color, Colorize};
+pub use diagnostic::{Diagnostic, Related, Suggestion};
+pub use ext::{OptionExt, ResultExt};
+pub use group::group;
 pub use level::LogLevel;
 pub use location::Location;
+pub use scope::scope;
 use std::{
+    borrow::Cow,
     env,
+    error::Error,
     fmt::{self, Debug, Display, Formatter},
+    iter,
 };
 
+#[derive(Clone)]
 pub struct Log {
     pub level: LogLevel,
-    pub message: String,
+    pub message: Cow<'static, str>,
     pub location: Option<Location>,
+    /// Short explanation of what is at the location, such as `expected u32`.
+    pub label: Option<String>,
     pub hint: Option<String>,
+    /// Identifier of the kind of log, such as `E0308`.
+    pub code: Option<String>,
+    pub causes: Vec<String>,
+    pub related: Vec<Related>,
+    pub suggestions: Vec<Suggestion>,
+    pub scopes: Vec<scope::Scope>,
+    pub target: Option<String>,
+    /// Amount of groups the log is nested in, see [`group`].
+    pub indent: usize,
+    /// Where in the program the log was created, set by the log macros.
+    pub caller: Option<Caller>,
+}
+
+/// Place in the source of the program that created a log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caller {
+    pub module_path: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl Caller {
+    /// Name of the crate the log was created in.
+    pub fn crate_name(&self) -> &'static str {
+        self.module_path
+            .split_once("::")
+            .map_or(self.module_path, |(name, _)| name)
+    }
 }
 
 impl Log {
-    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+    pub fn new(level: LogLevel, message: impl Into<Cow<'static, str>>) -> Self {
         Self {
             level,
             message: message.into(),
             location: None,
+            label: None,
             hint: None,
+            code: None,
+            causes: Vec::new(),
+            related: Vec::new(),
+            suggestions: Vec::new(),
+            scopes: scope::current(),
+            target: None,
+            indent: group::depth(),
+            caller: None,
         }
     }
 
-    pub fn message(mut self, message: impl Into<String>) -> Self {
+    pub fn message(mut self, message: impl Into<Cow<'static, str>>) -> Self {
         self.message = message.into();
         self
     }
@@ -47,68 +125,233 @@ impl Log {
         self
     }
 
+    /// Sets the label shown with the location.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     pub fn hint(mut self, hint: impl Into<String>) -> Self {
         self.hint = Some(hint.into());
         self
     }
 
+    /// Sets the code shown next to the level, such as `E0308`.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Appends `err` and every error in its [`Error::source`] chain as causes.
+    pub fn caused_by(mut self, err: &dyn Error) -> Self {
+        self.causes
+            .extend(error::chain(err).map(ToString::to_string));
+        self
+    }
+
+    /// Adds a secondary location with a message explaining how it relates to this log.
+    pub fn related(mut self, location: Location, message: impl Into<String>) -> Self {
+        self.related.push(Related {
+            location,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Adds a suggested replacement of the source at `location`.
+    pub fn suggestion(
+        mut self,
+        location: Location,
+        replacement: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            location,
+            replacement: replacement.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Sets the target tag shown next to the level, such as the module that emitted the log.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn caller(mut self, caller: Caller) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Outputs the log and terminates the process with the default [`fatal::exit_code`].
+    pub fn exit(self) -> ! {
+        self.exit_with(fatal::exit_code())
+    }
+
+    /// Outputs the log and terminates the process with `code`.
+    pub fn exit_with(self, code: i32) -> ! {
+        self.output();
+        fatal::exit(code)
+    }
+
+    /// Writes the log to every configured [`sink`], if its level is [`level::enabled`], and
+    /// keeps it in the [`history`]. Fatal logs dump the history first.
     pub fn output(self) {
-        print!("{self}");
+        group::record(self.level);
+
+        if level::enabled(self.level) {
+            if self.level == LogLevel::Fatal {
+                history::dump();
+            }
+
+            sink::write(&self);
+        }
+
+        history::record(self);
     }
 }
 
 impl Display for Log {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let last_line_string = if let Some(ref location) = self.location
-            && let Some(ref section) = location.section
-        {
-            (section.lines().end() + 1).to_string()
-        } else {
-            String::new()
+        let app_name = env::var("LOGGER_APP_NAME").unwrap_or_default();
+        self.render(f, &app_name)
+    }
+}
+
+impl Log {
+    /// Renders the log with `app_name` instead of the one set by [`set_app_name!`].
+    pub(crate) fn render(&self, f: &mut Formatter<'_>, app_name: &str) -> fmt::Result {
+        let unindented = Unindented {
+            log: self,
+            app_name,
         };
 
+        if self.indent == 0 {
+            return unindented.fmt(f);
+        }
+
+        let indent = "  ".repeat(self.indent);
+        for line in unindented.to_string().lines() {
+            writeln!(f, "{indent}{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a log without the indentation of its groups.
+struct Unindented<'a> {
+    log: &'a Log,
+    app_name: &'a str,
+}
+
+impl Display for Unindented<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Unindented { log, app_name } = *self;
+        let last_line_string = log
+            .location
+            .iter()
+            .chain(log.related.iter().map(|related| &related.location))
+            .flat_map(|location| iter::once(location).chain(location.origins()))
+            .filter_map(|location| location.section.as_ref())
+            .map(|section| section.lines().end() + 1)
+            .max()
+            .map(|last_line| last_line.to_string())
+            .unwrap_or_default();
+
         let padding_size = last_line_string.len() + 1;
         let padding = " ".repeat(padding_size);
 
-        let app_name = env::var("LOGGER_APP_NAME").unwrap_or_default();
+        let tags = [log.code.as_deref(), Some(app_name), log.target.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| format!("[{tag}]"))
+            .collect::<Vec<_>>()
+            .concat();
 
         // Log level and message
         writeln!(
             f,
             "{}{}",
-            if app_name.is_empty() {
-                self.level.to_string().color(self.level).bold()
-            } else {
-                format!("{}[{}]", self.level.to_string().color(self.level), app_name).bold()
-            },
-            format!(": {}", self.message).bold()
+            format!("{}{}", log.level.to_string().color(log.level), tags).bold(),
+            format!(": {}", log.message).bold()
         )?;
 
-        let section_is_valid = |s: &Section| s.lines().start() != s.lines().end();
+        // Scopes
+        if !log.scopes.is_empty() {
+            let scopes = log
+                .scopes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" > ");
+
+            write_note(f, &padding, "in", &scopes)?;
+        }
 
         // Location
-        if let Some(location) = &self.location {
+        if let Some(location) = &log.location {
             writeln!(f, "{}{} {}", &padding[1..], "-->".blue().bold(), location)?;
 
-            if location.section.as_ref().is_some_and(section_is_valid) {
-                // Source
-                writeln!(f, "{}{}", padding, "|".blue().bold())?;
+            if has_snippet(location) {
+                write_snippet(f, location, &padding, log.level)?;
+            }
 
-                let source = utils::remove_excess_tabs(&location.text);
-                highlight_source(f, source, location, &padding, self.level)?;
+            if let Some(label) = &log.label {
+                writeln!(
+                    f,
+                    "{}{} {}",
+                    padding,
+                    "|".blue().bold(),
+                    label.color(log.level).bold()
+                )?;
             }
+
+            write_origins(f, location, &padding)?;
         }
 
-        // Hint
-        if let Some(hint) = &self.hint {
-            if self
-                .location
-                .as_ref()
-                .is_some_and(|location| location.section.as_ref().is_some_and(section_is_valid))
-            {
-                writeln!(f, "{}{}", padding, "|".blue().bold())?;
+        // Related locations
+        for related in &log.related {
+            write_note(f, &padding, "note:", &related.message)?;
+            writeln!(
+                f,
+                "{}{} {}",
+                &padding[1..],
+                ":::".blue().bold(),
+                related.location
+            )?;
+
+            if has_snippet(&related.location) {
+                write_snippet(f, &related.location, &padding, LogLevel::Info)?;
             }
 
+            write_origins(f, &related.location, &padding)?;
+        }
+
+        // Suggestions
+        for suggestion in &log.suggestions {
+            write_note(
+                f,
+                &padding,
+                "suggestion:",
+                &format!("{}: `{}`", suggestion.message, suggestion.replacement),
+            )?;
+        }
+
+        // Causes
+        for (depth, cause) in log.causes.iter().enumerate() {
+            write_note(
+                f,
+                &padding,
+                &format!("{}caused by:", "  ".repeat(depth)),
+                cause,
+            )?;
+        }
+
+        // Hint
+        if let Some(hint) = &log.hint {
             writeln!(
                 f,
                 "{}{} {} {}",
@@ -117,18 +360,66 @@ impl Display for Log {
                 "help:".bold(),
                 hint
             )?;
-        } else if self
-            .location
-            .as_ref()
-            .is_some_and(|location| location.section.as_ref().is_some_and(section_is_valid))
-        {
-            writeln!(f, "{}{}", padding, "|".blue().bold())?;
         }
 
         Ok(())
     }
 }
 
+/// Writes a `= label: text` line below the log.
+fn write_note(f: &mut Formatter<'_>, padding: &str, label: &str, text: &str) -> fmt::Result {
+    writeln!(
+        f,
+        "{}{} {} {}",
+        padding,
+        "=".blue().bold(),
+        label.bold(),
+        text
+    )
+}
+
+/// Writes the locations that `location` was generated from, closest first.
+fn write_origins(f: &mut Formatter<'_>, location: &Location, padding: &str) -> fmt::Result {
+    for origin in location.origins() {
+        write_note(f, padding, "note:", "generated from")?;
+        writeln!(f, "{}{} {}", &padding[1..], ":::".blue().bold(), origin)?;
+
+        if has_snippet(origin) {
+            write_snippet(f, origin, padding, LogLevel::Info)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the section of `location` spans enough lines to show its source.
+fn has_snippet(location: &Location) -> bool {
+    location
+        .section
+        .as_ref()
+        .is_some_and(|section| section.lines().start() != section.lines().end())
+}
+
+/// Writes the highlighted source of `location` between two gutter lines.
+fn write_snippet(
+    f: &mut Formatter<'_>,
+    location: &Location,
+    padding: &str,
+    level: LogLevel,
+) -> fmt::Result {
+    let text = match location.text() {
+        Ok(text) => text,
+        Err(err) => return write_note(f, padding, "note:", &format!("source not shown, {err}")),
+    };
+
+    writeln!(f, "{}{}", padding, "|".blue().bold())?;
+
+    let source = utils::remove_excess_tabs(text);
+    highlight_source(f, source, location, padding, level)?;
+
+    writeln!(f, "{}{}", padding, "|".blue().bold())
+}
+
 impl Debug for Log {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{self}")
@@ -146,7 +437,7 @@ pub fn highlight_source<S: Into<String>>(
 ) -> fmt::Result {
     let section = &location.section.clone().unwrap_or(Section::full());
     let highlighted = match level {
-        LogLevel::Trace | LogLevel::Debug | LogLevel::Info => {
+        LogLevel::Trace | LogLevel::Debug | LogLevel::Info | LogLevel::Note | LogLevel::Success => {
             utils::bold_highlight(source, section)
         }
         _ => utils::highlight(source, section, level.into()),
@@ -1,8 +1,13 @@
 use std::{
+    borrow::Cow,
     fmt::{self, Debug, Display},
-    fs, io,
+    fs::{self, File, Metadata},
+    io::{self, BufRead, BufReader},
+    iter,
     ops::RangeInclusive,
     path::PathBuf,
+    str,
+    time::SystemTime,
 };
 
 #[derive(Clone)]
@@ -71,8 +76,42 @@ impl Debug for Section {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Location {
     pub path: Option<PathBuf>,
-    pub text: String,
+    /// Name of a source that is not a file, such as `<repl:3>`, shown when there is no path.
+    pub name: Option<String>,
+    source: Source,
     pub section: Option<Section>,
+    /// Location of the source this location was generated from, such as a macro invocation or
+    /// a template.
+    pub generated_from: Option<Box<Location>>,
+}
+
+/// Where [`Location::text`] gets the source from.
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Text(String),
+    /// A file read when the text is needed, with its stamp when the location was created, `None`
+    /// if it could not be read.
+    File {
+        path: PathBuf,
+        stamp: Option<FileStamp>,
+    },
+}
+
+/// Size and modification time of a file, used to notice that it changed after a lazy
+/// [`Location`] pointing into it was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl From<&Metadata> for FileStamp {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
 }
 
 impl Location {
@@ -84,23 +123,116 @@ impl Location {
 
         Ok(Self {
             path: Some(path),
-            text,
-            section: None,
+            ..Self::from_text(text)
         })
     }
 
+    /// Creates a location that reads its text from `path` only when it is rendered, so creating
+    /// it cannot fail. Only the lines up to the end of the section are read.
+    pub fn lazy(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let stamp = fs::metadata(&path)
+            .ok()
+            .map(|metadata| FileStamp::from(&metadata));
+
+        Self {
+            path: Some(path.clone()),
+            source: Source::File { path, stamp },
+            ..Self::from_text(String::new())
+        }
+    }
+
+    /// Creates a location pointing at a 1-based line of a file, without reading the file.
+    pub fn from_path_line(path: impl Into<PathBuf>, line: usize) -> Self {
+        let line = line.saturating_sub(1);
+
+        Self {
+            path: Some(path.into()),
+            section: Some(Section::new(line..=line, 0..=0)),
+            ..Self::from_text(String::new())
+        }
+    }
+
     pub fn from_text(text: impl Into<String>) -> Self {
         Self {
             path: None,
-            text: text.into(),
+            name: None,
+            source: Source::Text(text.into()),
             section: None,
+            generated_from: None,
+        }
+    }
+
+    /// Creates a location in an in-memory source, such as `<repl:3>` or
+    /// `template.html (generated)`.
+    pub fn named(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::from_text(text)
         }
     }
 
+    /// Sets the path shown for the location, the text is not read from it.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
     pub fn section(mut self, section: Section) -> Self {
         self.section = Some(section);
         self
     }
+
+    /// Sets the location this one was generated from, which can itself be generated.
+    pub fn generated_from(mut self, origin: Location) -> Self {
+        self.generated_from = Some(Box::new(origin));
+        self
+    }
+
+    /// Returns the source text. Lazy locations read it from their file, up to the end of the
+    /// section, so line numbers and byte offsets still match the file.
+    ///
+    /// # Errors
+    /// Returns an error if the file of a lazy location cannot be read, is not UTF-8, or changed
+    /// since the location was created.
+    pub fn text(&self) -> io::Result<Cow<'_, str>> {
+        let (path, stamp) = match &self.source {
+            Source::Text(text) => return Ok(Cow::Borrowed(text)),
+            Source::File { path, stamp } => (path, *stamp),
+        };
+
+        let file = File::open(path)?;
+        if Some(FileStamp::from(&file.metadata()?)) != stamp {
+            return Err(io::Error::other(
+                "the file changed since the log was created",
+            ));
+        }
+
+        let last = self
+            .section
+            .as_ref()
+            .map_or(usize::MAX, |section| *section.lines().end());
+
+        let mut reader = BufReader::new(file);
+        let mut text = Vec::new();
+
+        for _ in 0..=last {
+            if reader.read_until(b'\n', &mut text)? == 0 {
+                break;
+            }
+        }
+
+        String::from_utf8(text)
+            .map(Cow::Owned)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Iterates over the locations this one was generated from, the closest one first.
+    pub fn origins(&self) -> impl Iterator<Item = &Location> {
+        iter::successors(self.generated_from.as_deref(), |location| {
+            location.generated_from.as_deref()
+        })
+    }
 }
 
 impl Display for Location {
@@ -110,6 +242,8 @@ impl Display for Location {
             "{}{}",
             if let Some(path) = &self.path {
                 path.display().to_string()
+            } else if let Some(name) = &self.name {
+                name.clone()
             } else {
                 "unknown".to_string()
             },
@@ -1,42 +1,242 @@
 //! Module for logging with the `log` crate.
 
-use crate::{Log, LogLevel};
+use crate::{
+    sink::{self, Sink, Stdout},
+    Caller, Location, Log, LogLevel,
+};
 use log::{LevelFilter, Metadata, Record, SetLoggerError};
+use std::{cell::Cell, str::FromStr, sync::OnceLock};
 
 static LOGGER: Logger = Logger;
+/// Set by [`init_with`] once [`LOGGER`] is the backend of the `log` crate.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+thread_local! {
+    /// Set while [`LogFacade`] forwards a log, so [`Logger`] prints it instead of feeding it back
+    /// into the sinks.
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether this crate's [`Logger`] was installed by [`init`] or [`init_with`], in which case
+/// forwarding logs to the `log` crate would only bring them back to the sinks.
+fn is_backend() -> bool {
+    CONFIG.get().is_some()
+}
 
 /// Set the global logger for the `log` crate.
 /// # Errors
 /// This function will return an error if the logger can't be set.
 pub fn init() -> Result<(), SetLoggerError> {
-    log::set_logger(&LOGGER).map(|()| log::set_max_level(LevelFilter::Info))
+    init_with(Config::default())
+}
+
+/// Set the global logger for the `log` crate with a custom configuration.
+/// # Errors
+/// This function will return an error if the logger can't be set.
+pub fn init_with(config: Config) -> Result<(), SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(config.max_level());
+    let _ = CONFIG.set(config);
+
+    Ok(())
+}
+
+/// Filtering configuration of the `log` bridge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Level used for targets that don't match any directive.
+    pub level: LevelFilter,
+    /// Per-target levels, the longest matching target prefix wins.
+    pub directives: Vec<(String, LevelFilter)>,
+}
+
+impl Config {
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level,
+            directives: Vec::new(),
+        }
+    }
+
+    pub fn directive(mut self, target: impl Into<String>, level: LevelFilter) -> Self {
+        self.directives.push((target.into(), level));
+        self
+    }
+
+    /// Returns the level filter that applies to `target`.
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new(LevelFilter::Info)
+    }
+}
+
+/// Parses directives in the `RUST_LOG` style, such as `info,my_crate::parser=trace`.
+impl FromStr for Config {
+    type Err = log::ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    config
+                        .directives
+                        .push((target.trim().to_string(), level.trim().parse()?));
+                }
+                None => config.level = directive.parse()?,
+            }
+        }
+
+        Ok(config)
+    }
 }
 
 pub struct Logger;
 
 impl log::Log for Logger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= log::max_level()
+        let level = CONFIG
+            .get()
+            .map_or_else(log::max_level, |config| config.level_for(metadata.target()));
+
+        metadata.level() <= level
     }
 
     fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // The logger was installed without `init`, and is the backend of a `LogFacade` sink.
+        if FORWARDING.get() {
+            Stdout.write(&Log::from(record));
+        } else {
             Log::from(record).output();
         }
     }
 
     fn flush(&self) {
+        sink::flush();
+    }
+}
+
+/// A sink that forwards logs to whichever `log` backend is installed, instead of printing them.
+/// The hint and location are passed along as key-values.
+///
+/// Logs created by the log macros keep the module, file and line of their call site, and their
+/// module as target unless they have one, so the backend can filter them per crate.
+///
+/// This is meant for library crates, the `log_facade` feature makes it the default sink. If the
+/// binary installs this crate's [`Logger`] as the backend, logs are written to [`Stdout`] instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogFacade;
+
+impl Sink for LogFacade {
+    fn write(&self, log: &Log) {
+        if is_backend() {
+            Stdout.write(log);
+            return;
+        }
+
+        let location = log.location.as_ref().map(ToString::to_string);
+        let key_values = [
+            location.as_deref().map(|location| ("location", location)),
+            log.hint.as_deref().map(|hint| ("hint", hint)),
+        ];
+
+        let caller = log.caller;
+
+        let path = log
+            .location
+            .as_ref()
+            .and_then(|location| location.path.as_ref())
+            .and_then(|path| path.to_str());
+
+        let line = log
+            .location
+            .as_ref()
+            .and_then(|location| location.section.as_ref())
+            .and_then(|section| u32::try_from(section.lines().end().saturating_add(1)).ok());
+
+        let target = log
+            .target
+            .as_deref()
+            .or(caller.map(|caller| caller.module_path))
+            .unwrap_or_default();
+
+        FORWARDING.set(true);
+        log::logger().log(
+            &Record::builder()
+                .args(format_args!("{}", log.message))
+                .level(log.level.into())
+                .target(target)
+                .module_path_static(caller.map(|caller| caller.module_path))
+                .file(caller.map(|caller| caller.file).or(path))
+                .line(caller.map(|caller| caller.line).or(line))
+                .key_values(&key_values)
+                .build(),
+        );
+        FORWARDING.set(false);
+    }
+
+    fn flush(&self) {
+        if is_backend() {
+            Stdout.flush();
+        } else {
+            log::logger().flush();
+        }
     }
 }
 
 #[cfg(feature = "log")]
 impl From<&log::Record<'_>> for Log {
     fn from(record: &log::Record) -> Self {
+        let location = record
+            .file()
+            .zip(record.line())
+            .map(|(path, line)| Location::from_path_line(path, line as usize));
+
+        let target = Some(record.target())
+            .filter(|target| !target.is_empty())
+            .or(record.module_path());
+
+        let caller = record
+            .module_path_static()
+            .zip(record.file_static())
+            .zip(record.line())
+            .map(|((module_path, file), line)| Caller {
+                module_path,
+                file,
+                line,
+            });
+
         Self {
-            level: record.level().into(),
-            message: record.args().to_string(),
-            location: None,
-            hint: None,
+            location,
+            target: target.map(ToString::to_string),
+            caller,
+            ..Log::new(record.level().into(), record.args().to_string())
         }
     }
 }
@@ -53,3 +253,89 @@ impl From<log::Level> for LogLevel {
         }
     }
 }
+
+impl From<LogLevel> for log::Level {
+    fn from(val: LogLevel) -> Self {
+        match val {
+            LogLevel::Trace => log::Level::Trace,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Info | LogLevel::Note | LogLevel::Success => log::Level::Info,
+            LogLevel::Warning => log::Level::Warn,
+            LogLevel::Error | LogLevel::Fatal => log::Level::Error,
+        }
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(val: LogLevel) -> Self {
+        log::Level::from(val).to_level_filter()
+    }
+}
+
+impl TryFrom<LevelFilter> for LogLevel {
+    type Error = LevelFilter;
+
+    /// Fails for [`LevelFilter::Off`], which has no matching level.
+    fn try_from(val: LevelFilter) -> Result<Self, LevelFilter> {
+        val.to_level().map(LogLevel::from).ok_or(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_directives() {
+        let config: Config = " warn , app::db=trace,, other = off ".parse().unwrap();
+
+        assert_eq!(
+            config,
+            Config::new(LevelFilter::Warn)
+                .directive("app::db", LevelFilter::Trace)
+                .directive("other", LevelFilter::Off)
+        );
+        assert_eq!(config.max_level(), LevelFilter::Trace);
+
+        assert_eq!("".parse(), Ok(Config::default()));
+        assert!("app=loud".parse::<Config>().is_err());
+        assert!("loud".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn longest_matching_prefix_wins() {
+        let config: Config = "info,foo=debug,foo::bar=trace,foo::bar::baz=error"
+            .parse()
+            .unwrap();
+
+        assert_eq!(config.level_for("foo"), LevelFilter::Debug);
+        assert_eq!(config.level_for("foo::qux"), LevelFilter::Debug);
+        assert_eq!(config.level_for("foo::bar"), LevelFilter::Trace);
+        assert_eq!(config.level_for("foo::bar::qux"), LevelFilter::Trace);
+        assert_eq!(config.level_for("foo::bar::baz"), LevelFilter::Error);
+        assert_eq!(config.level_for("foo::barbaz"), LevelFilter::Debug);
+
+        // A prefix only matches whole path segments.
+        assert_eq!(config.level_for("foobar"), LevelFilter::Info);
+        assert_eq!(config.level_for("fo"), LevelFilter::Info);
+        assert_eq!(config.level_for(""), LevelFilter::Info);
+    }
+
+    #[test]
+    fn converts_level_filters() {
+        assert_eq!(LogLevel::try_from(LevelFilter::Warn), Ok(LogLevel::Warning));
+        assert_eq!(LogLevel::try_from(LevelFilter::Trace), Ok(LogLevel::Trace));
+        assert_eq!(LogLevel::try_from(LevelFilter::Off), Err(LevelFilter::Off));
+
+        for level in LogLevel::all() {
+            let filter = LevelFilter::from(level);
+            assert_eq!(
+                LogLevel::try_from(filter),
+                Ok(log::Level::from(level).into())
+            );
+        }
+
+        assert_eq!(LevelFilter::from(LogLevel::Fatal), LevelFilter::Error);
+        assert_eq!(LevelFilter::from(LogLevel::Note), LevelFilter::Info);
+    }
+}
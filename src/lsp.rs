@@ -0,0 +1,150 @@
+//! Conversion of logs into Language Server Protocol diagnostics.
+
+use crate::{
+    json::{self, Value},
+    utils, Location, Log, LogLevel,
+};
+use std::{
+    fmt::Write,
+    path::{self, Path},
+};
+
+/// Builds the `PublishDiagnosticsParams` for a document from the logs reported for it.
+pub fn publish_diagnostics(uri: &str, version: Option<usize>, logs: &[Log]) -> Value {
+    json::object([
+        ("uri", uri.into()),
+        ("version", version.into()),
+        (
+            "diagnostics",
+            Value::Array(logs.iter().map(diagnostic).collect()),
+        ),
+    ])
+}
+
+/// Converts a log into an LSP `Diagnostic`.
+///
+/// Secondary locations become `relatedInformation`, and so does the hint, attached to the
+/// primary location. Causes are appended to the message.
+pub fn diagnostic(log: &Log) -> Value {
+    let severity: usize = match log.level {
+        LogLevel::Error | LogLevel::Fatal => 1,
+        LogLevel::Warning => 2,
+        LogLevel::Info | LogLevel::Note | LogLevel::Success => 3,
+        LogLevel::Trace | LogLevel::Debug => 4,
+    };
+
+    let mut message = log.message.to_string();
+    for cause in &log.causes {
+        message.push_str("\ncaused by: ");
+        message.push_str(cause);
+    }
+
+    let mut related = log
+        .related
+        .iter()
+        .filter_map(|related| related_information(&related.location, &related.message))
+        .collect::<Vec<_>>();
+
+    if let Some(location) = &log.location {
+        if let Some(label) = &log.label {
+            related.extend(related_information(location, label));
+        }
+        if let Some(hint) = &log.hint {
+            related.extend(related_information(location, &format!("help: {hint}")));
+        }
+    }
+
+    json::object([
+        (
+            "range",
+            log.location.as_ref().map_or_else(empty_range, range),
+        ),
+        ("severity", severity.into()),
+        ("code", log.code.clone().into()),
+        (
+            "source",
+            log.target
+                .as_deref()
+                .or(log.caller.map(|caller| caller.crate_name()))
+                .into(),
+        ),
+        ("message", message.into()),
+        (
+            "relatedInformation",
+            if related.is_empty() {
+                Value::Null
+            } else {
+                Value::Array(related)
+            },
+        ),
+    ])
+}
+
+/// Converts a path into a `file://` URI, resolving it against the current directory.
+pub fn file_uri(path: &Path) -> String {
+    let path = path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut path = path.display().to_string().replace('\\', "/");
+
+    // Windows paths start with the drive letter instead of a slash.
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char);
+            }
+            _ => {
+                let _ = write!(uri, "%{byte:02X}");
+            }
+        }
+    }
+
+    uri
+}
+
+fn related_information(location: &Location, message: &str) -> Option<Value> {
+    let path = location.path.as_ref()?;
+
+    Some(json::object([
+        (
+            "location",
+            json::object([("uri", file_uri(path).into()), ("range", range(location))]),
+        ),
+        ("message", message.into()),
+    ]))
+}
+
+/// 0-based range of a location, with columns counted in UTF-16 code units.
+fn range(location: &Location) -> Value {
+    let Some(section) = &location.section else {
+        return empty_range();
+    };
+
+    let text = location.text().unwrap_or_default();
+    let lines = text.lines().collect::<Vec<_>>();
+    let position = |line: usize, col: usize| {
+        let character = lines
+            .get(line)
+            .map_or(col, |text| utils::utf16_column(text, col));
+        json::object([("line", line.into()), ("character", character.into())])
+    };
+
+    json::object([
+        (
+            "start",
+            position(*section.lines().start(), *section.cols().start()),
+        ),
+        (
+            "end",
+            position(*section.lines().end(), *section.cols().end()),
+        ),
+    ])
+}
+
+fn empty_range() -> Value {
+    let position = || json::object([("line", 0.into()), ("character", 0.into())]);
+    json::object([("start", position()), ("end", position())])
+}
@@ -0,0 +1,373 @@
+//! Sinks shipping logs as JSON lines to a collector over TCP, UDP or a Unix socket.
+//!
+//! Logs are queued and sent from a background thread, so a slow or unreachable collector never
+//! blocks the caller. While disconnected, logs are kept in a bounded buffer and the connection
+//! is retried with exponential backoff.
+
+use crate::{json::Value, sink::Sink, Log};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::Duration,
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::PathBuf};
+
+/// Amount of logs buffered by default.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum time [`Sink::flush`] waits for the buffer to be sent.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Tcp(SocketAddr),
+    Udp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+enum Connection {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Transport {
+    fn connect(&self) -> io::Result<Connection> {
+        match self {
+            Self::Tcp(address) => {
+                let stream = TcpStream::connect_timeout(address, TIMEOUT)?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Ok(Connection::Tcp(stream))
+            }
+            Self::Udp(address) => {
+                let local: SocketAddr = if address.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0; 16], 0).into()
+                };
+
+                let socket = UdpSocket::bind(local)?;
+                socket.connect(address)?;
+                Ok(Connection::Udp(socket))
+            }
+            #[cfg(unix)]
+            Self::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Ok(Connection::Unix(stream))
+            }
+        }
+    }
+}
+
+impl Connection {
+    fn send(&mut self, line: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.write_all(line),
+            Self::Udp(socket) => socket.send(line).map(|_| ()),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write_all(line),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    lines: VecDeque<String>,
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    /// Notified when a line is queued or the sink is closed.
+    queued: Condvar,
+    /// Notified when a line is sent.
+    sent: Condvar,
+    dropped: AtomicU64,
+    started: AtomicBool,
+    /// Amount of [`Network`] handles, the sink is closed when the last one is dropped.
+    handles: AtomicUsize,
+}
+
+impl Shared {
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Ships every log as a line of JSON, see [`Value`]'s `From<&Log>` implementation in
+/// [`json`](crate::json).
+///
+/// Clones share the same connection and buffer, so a clone can be kept to read
+/// [`Network::dropped`] after adding the sink.
+#[derive(Debug)]
+pub struct Network {
+    transport: Transport,
+    capacity: usize,
+    max_backoff: Duration,
+    shared: Arc<Shared>,
+}
+
+impl Network {
+    /// # Errors
+    /// This function will propagate errors from resolving the address.
+    pub fn tcp(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self::new(Transport::Tcp(resolve(address)?)))
+    }
+
+    /// Sends every log in its own datagram.
+    ///
+    /// # Errors
+    /// This function will propagate errors from resolving the address.
+    pub fn udp(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self::new(Transport::Udp(resolve(address)?)))
+    }
+
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        Self::new(Transport::Unix(path.into()))
+    }
+
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            capacity: DEFAULT_CAPACITY,
+            max_backoff: MAX_BACKOFF,
+            shared: Arc::new(Shared {
+                handles: AtomicUsize::new(1),
+                ..Shared::default()
+            }),
+        }
+    }
+
+    /// Sets the amount of logs buffered while the collector is unreachable, logs written while
+    /// the buffer is full are dropped.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the longest time to wait between two connection attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff.max(MIN_BACKOFF);
+        self
+    }
+
+    /// Amount of logs dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    fn start(&self) {
+        if self.shared.started.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let shared = Arc::clone(&self.shared);
+        let transport = self.transport.clone();
+        let max_backoff = self.max_backoff;
+
+        thread::Builder::new()
+            .name("logger-network".to_string())
+            .spawn(move || run(&shared, &transport, max_backoff))
+            .expect("Failed to spawn the network sink thread");
+    }
+}
+
+impl Sink for Network {
+    fn write(&self, log: &Log) {
+        {
+            let mut queue = self.shared.queue();
+            if queue.lines.len() >= self.capacity {
+                self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+
+            queue.lines.push_back(format!("{}\n", Value::from(log)));
+            self.shared.queued.notify_one();
+        }
+
+        self.start();
+    }
+
+    /// Waits for the buffered logs to be sent, if the collector is reachable.
+    fn flush(&self) {
+        let queue = self.shared.queue();
+        let _ = self
+            .shared
+            .sent
+            .wait_timeout_while(queue, FLUSH_TIMEOUT, |queue| !queue.lines.is_empty());
+    }
+}
+
+impl Clone for Network {
+    fn clone(&self) -> Self {
+        self.shared.handles.fetch_add(1, Ordering::Relaxed);
+
+        Self {
+            transport: self.transport.clone(),
+            capacity: self.capacity,
+            max_backoff: self.max_backoff,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        if self.shared.handles.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.queue().closed = true;
+            self.shared.queued.notify_one();
+        }
+    }
+}
+
+/// Sends queued lines until the sink is closed and every line is sent.
+fn run(shared: &Shared, transport: &Transport, max_backoff: Duration) {
+    let mut connection = None;
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        let line = {
+            let queue = shared
+                .queued
+                .wait_while(shared.queue(), |queue| {
+                    queue.lines.is_empty() && !queue.closed
+                })
+                .unwrap_or_else(PoisonError::into_inner);
+
+            match queue.lines.front() {
+                Some(line) => line.clone(),
+                None => return,
+            }
+        };
+
+        if connection.is_none() {
+            if let Ok(new) = transport.connect() {
+                connection = Some(new);
+                backoff = MIN_BACKOFF;
+            } else {
+                if shared.queue().closed {
+                    return;
+                }
+
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(max_backoff);
+                continue;
+            }
+        }
+
+        let Some(connected) = &mut connection else {
+            continue;
+        };
+
+        if connected.send(line.as_bytes()).is_ok() {
+            shared.queue().lines.pop_front();
+            shared.sent.notify_all();
+        } else {
+            connection = None;
+        }
+    }
+}
+
+fn resolve(address: impl ToSocketAddrs) -> io::Result<SocketAddr> {
+    address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    fn read_messages(listener: &TcpListener, count: usize) -> Vec<String> {
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+        BufReader::new(stream)
+            .lines()
+            .take(count)
+            .map(|line| {
+                let value = line.unwrap().parse::<Value>().unwrap();
+                value.get("message").unwrap().as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    /// Address with nothing listening on it.
+    fn unreachable() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[test]
+    fn sends_json_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let network = Network::tcp(listener.local_addr().unwrap()).unwrap();
+
+        network.write(&Log::new(LogLevel::Info, "first"));
+        network.write(&Log::new(LogLevel::Error, "second"));
+
+        assert_eq!(read_messages(&listener, 2), ["first", "second"]);
+    }
+
+    #[test]
+    fn keeps_sending_after_the_handle_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let network = Network::tcp(listener.local_addr().unwrap()).unwrap();
+        let sink = network.clone();
+        drop(network);
+
+        sink.write(&Log::new(LogLevel::Info, "still sent"));
+
+        assert_eq!(read_messages(&listener, 1), ["still sent"]);
+    }
+
+    #[test]
+    fn buffers_until_the_collector_is_reachable() {
+        let address = unreachable();
+        let network = Network::tcp(address)
+            .unwrap()
+            .max_backoff(Duration::from_millis(100));
+
+        network.write(&Log::new(LogLevel::Info, "first"));
+        network.write(&Log::new(LogLevel::Info, "second"));
+        thread::sleep(Duration::from_millis(300));
+
+        let listener = TcpListener::bind(address).unwrap();
+        assert_eq!(read_messages(&listener, 2), ["first", "second"]);
+        assert_eq!(network.dropped(), 0);
+    }
+
+    #[test]
+    fn drops_logs_when_the_buffer_is_full() {
+        let network = Network::tcp(unreachable()).unwrap().capacity(2);
+
+        for _ in 0..5 {
+            network.write(&Log::new(LogLevel::Info, "lost"));
+        }
+
+        assert_eq!(network.dropped(), 3);
+    }
+}
@@ -1,14 +1,10 @@
 use crate::{Location, Log, LogLevel};
-use std::{
-    panic::{self, PanicHookInfo},
-    process,
-};
+use std::panic::{self, PanicHookInfo};
 
 /// Sets a panic hook that will print a fatal log on panic.
 pub fn set_panic_hook() {
     panic::set_hook(Box::new(|panic_info| {
-        Log::from(panic_info).output();
-        process::exit(1);
+        Log::from(panic_info).exit();
     }));
 }
 
@@ -29,10 +25,8 @@ impl From<&PanicHookInfo<'_>> for Log {
         };
 
         Log {
-            message,
-            level: LogLevel::Fatal,
             location,
-            hint: None,
+            ..Log::new(LogLevel::Fatal, message)
         }
     }
 }
@@ -51,23 +45,12 @@ impl From<&panic::Location<'_>> for Location {
                 ),
         );
 
-        let location = Location::from_path(path);
-        match location {
-            Ok(location) => {
-                #[cfg(debug_assertions)]
-                return location.section(section);
+        let location = Location::from_path(path)
+            .unwrap_or_else(|_| Location::from_text(String::new()).path(path));
 
-                #[cfg(not(debug_assertions))]
-                return location;
-            }
-            Err(_) => Location {
-                path: Some(path.into()),
-                text: String::new(),
-                #[cfg(debug_assertions)]
-                section: Some(section),
-                #[cfg(not(debug_assertions))]
-                section: None,
-            },
-        }
+        #[cfg(debug_assertions)]
+        let location = location.section(section);
+
+        location
     }
 }
@@ -0,0 +1,253 @@
+//! Progress bars and status lines that stay below log output.
+//!
+//! Bars are drawn to stderr. When stderr is a terminal, logs written by the [`Stdout`] and
+//! [`Stderr`] sinks clear the bars first and redraw them afterwards. Otherwise the bars are
+//! printed as plain lines, at most once per [`PLAIN_INTERVAL`].
+//!
+//! [`Stdout`]: crate::sink::Stdout
+//! [`Stderr`]: crate::sink::Stderr
+
+use crate::Colorize;
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// Minimum time between two redraws on a terminal.
+const DRAW_INTERVAL: Duration = Duration::from_millis(50);
+/// Minimum time between two plain progress lines of the same bar when not on a terminal.
+pub const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const BAR_WIDTH: usize = 30;
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    bars: Vec::new(),
+    drawn_lines: 0,
+    last_draw: None,
+    suspended: 0,
+});
+
+struct Bar {
+    id: u64,
+    message: String,
+    position: u64,
+    length: Option<u64>,
+    started: Instant,
+    last_plain: Option<Instant>,
+}
+
+impl Bar {
+    fn render(&self) -> String {
+        if let Some(length) = self.length {
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_precision_loss,
+                clippy::cast_sign_loss,
+                reason = "The ratio is clamped between 0 and 1"
+            )]
+            let filled = ((self.position as f64 / length.max(1) as f64).clamp(0.0, 1.0)
+                * BAR_WIDTH as f64) as usize;
+
+            format!(
+                "[{}{}] {}/{} {}",
+                "=".repeat(filled).blue().bold(),
+                " ".repeat(BAR_WIDTH - filled),
+                self.position,
+                length,
+                self.message
+            )
+        } else {
+            let frame = self.started.elapsed().as_millis() / 80 % SPINNER.len() as u128;
+            format!(
+                "{} {}",
+                SPINNER[frame as usize].to_string().blue().bold(),
+                self.message
+            )
+        }
+    }
+}
+
+struct Registry {
+    bars: Vec<Bar>,
+    drawn_lines: usize,
+    last_draw: Option<Instant>,
+    /// Amount of [`suspend`] calls in progress, the bars are not drawn while it is non-zero.
+    suspended: usize,
+}
+
+impl Registry {
+    fn clear(&mut self, stderr: &mut impl Write) {
+        if self.drawn_lines > 0 {
+            let _ = write!(stderr, "\x1b[{}A\x1b[J", self.drawn_lines);
+            self.drawn_lines = 0;
+        }
+    }
+
+    fn draw(&mut self, stderr: &mut impl Write) {
+        if self.suspended > 0 {
+            return;
+        }
+
+        for bar in &self.bars {
+            let _ = writeln!(stderr, "{}", bar.render());
+        }
+
+        self.drawn_lines = self.bars.len();
+        self.last_draw = Some(Instant::now());
+        let _ = stderr.flush();
+    }
+
+    /// Redraws the bars on a terminal, or prints plain lines for bars that are due otherwise.
+    fn refresh(&mut self, force: bool) {
+        let mut stderr = io::stderr().lock();
+
+        if stderr.is_terminal() {
+            if force
+                || self
+                    .last_draw
+                    .is_none_or(|last| last.elapsed() >= DRAW_INTERVAL)
+            {
+                self.clear(&mut stderr);
+                self.draw(&mut stderr);
+            }
+        } else {
+            for bar in &mut self.bars {
+                if bar
+                    .last_plain
+                    .is_none_or(|last| last.elapsed() >= PLAIN_INTERVAL)
+                {
+                    let _ = writeln!(stderr, "{}", bar.render());
+                    bar.last_plain = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, id: u64, update: impl FnOnce(&mut Bar)) {
+        if let Some(bar) = self.bars.iter_mut().find(|bar| bar.id == id) {
+            update(bar);
+        }
+
+        self.refresh(false);
+    }
+}
+
+fn bars() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Handle to a progress bar or status line, it is removed when finished or dropped.
+#[derive(Debug)]
+pub struct ProgressBar {
+    id: u64,
+}
+
+impl ProgressBar {
+    fn new(length: Option<u64>, message: String) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let mut bars = bars();
+        bars.bars.push(Bar {
+            id,
+            message,
+            position: 0,
+            length,
+            started: Instant::now(),
+            last_plain: None,
+        });
+        bars.refresh(true);
+
+        Self { id }
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        let message = message.into();
+        bars().update(self.id, |bar| bar.message = message);
+    }
+
+    pub fn set_position(&self, position: u64) {
+        bars().update(self.id, |bar| bar.position = position);
+    }
+
+    pub fn inc(&self, delta: u64) {
+        bars().update(self.id, |bar| {
+            bar.position = bar.position.saturating_add(delta);
+        });
+    }
+
+    /// Redraws the bar, this advances the spinner of status lines.
+    pub fn tick(&self) {
+        bars().update(self.id, |_| {});
+    }
+
+    /// Removes the bar.
+    pub fn finish(self) {}
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        let mut bars = bars();
+        let mut stderr = io::stderr().lock();
+
+        if stderr.is_terminal() {
+            bars.clear(&mut stderr);
+            bars.bars.retain(|bar| bar.id != self.id);
+            bars.draw(&mut stderr);
+        } else if let Some(index) = bars.bars.iter().position(|bar| bar.id == self.id) {
+            let _ = writeln!(stderr, "{}", bars.bars.remove(index).render());
+        }
+    }
+}
+
+/// Adds a progress bar going from 0 to `length`.
+pub fn bar(length: u64, message: impl Into<String>) -> ProgressBar {
+    ProgressBar::new(Some(length), message.into())
+}
+
+/// Adds a status line with a spinner.
+pub fn status(message: impl Into<String>) -> ProgressBar {
+    ProgressBar::new(None, message.into())
+}
+
+/// Clears the bars while `f` writes to the terminal and redraws them afterwards.
+///
+/// The bars are not locked while `f` runs, so `f` can log, or panic into a hook that logs.
+/// Updates from other threads are not drawn until every suspended output is written.
+pub fn suspend<T>(f: impl FnOnce() -> T) -> T {
+    {
+        let mut bars = bars();
+        if bars.bars.is_empty() || !io::stderr().is_terminal() {
+            drop(bars);
+            return f();
+        }
+
+        bars.clear(&mut io::stderr().lock());
+        bars.suspended += 1;
+    }
+
+    let _resume = Resume;
+    f()
+}
+
+/// Ends a [`suspend`] when dropped, even if its output panicked, and redraws the bars once no
+/// other output is suspended.
+struct Resume;
+
+impl Drop for Resume {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+
+        let mut bars = bars();
+        bars.suspended -= 1;
+
+        let mut stderr = io::stderr().lock();
+        bars.clear(&mut stderr);
+        bars.draw(&mut stderr);
+    }
+}
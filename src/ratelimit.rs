@@ -0,0 +1,247 @@
+//! Per call site state used by the `*_once!`, `*_every!` and `*_sampled!` macros.
+//!
+//! The amount of suppressed logs is summarized before the next log of the same call site.
+//! Summaries still pending when the burst ends are output by [`report`], which
+//! [`sink::flush`](crate::sink::flush) calls.
+
+use crate::{Caller, Log, LogLevel};
+use std::{
+    borrow::Cow,
+    mem,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Call sites with suppressed logs that were not summarized yet.
+static PENDING: Mutex<Vec<&'static CallSite>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+struct State {
+    last: Option<Instant>,
+    calls: u64,
+    suppressed: u64,
+    /// Whether the call site is in [`PENDING`].
+    pending: bool,
+}
+
+/// Tracks how often a single log call site has been hit.
+#[derive(Debug)]
+pub struct CallSite {
+    level: LogLevel,
+    caller: Caller,
+    state: Mutex<State>,
+}
+
+impl CallSite {
+    pub const fn new(level: LogLevel, caller: Caller) -> Self {
+        Self {
+            level,
+            caller,
+            state: Mutex::new(State {
+                last: None,
+                calls: 0,
+                suppressed: 0,
+                pending: false,
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns `true` only the first time it is called.
+    pub fn once(&self) -> bool {
+        let mut state = self.state();
+        state.calls += 1;
+        state.calls == 1
+    }
+
+    /// Returns the amount of suppressed calls if at least `period` has passed since the last
+    /// emitted call, otherwise suppresses this call and returns `None`.
+    pub fn every(&'static self, period: Duration) -> Option<u64> {
+        let mut state = self.state();
+        let now = Instant::now();
+
+        if state
+            .last
+            .is_some_and(|last| now.duration_since(last) < period)
+        {
+            self.suppress(&mut state);
+            return None;
+        }
+
+        state.last = Some(now);
+        Some(mem::take(&mut state.suppressed))
+    }
+
+    /// Returns the amount of suppressed calls for one in every `n` calls, otherwise suppresses
+    /// this call and returns `None`.
+    pub fn sampled(&'static self, n: u64) -> Option<u64> {
+        let mut state = self.state();
+        state.calls += 1;
+
+        if !(state.calls - 1).is_multiple_of(n.max(1)) {
+            self.suppress(&mut state);
+            return None;
+        }
+
+        Some(mem::take(&mut state.suppressed))
+    }
+
+    /// Outputs a summary of the suppressed calls returned by [`CallSite::every`] or
+    /// [`CallSite::sampled`], if there are any.
+    pub fn summarize(&self, suppressed: u64) {
+        if let Some(log) = self.summary(suppressed) {
+            log.output();
+        }
+    }
+
+    fn suppress(&'static self, state: &mut State) {
+        state.suppressed += 1;
+
+        if !state.pending {
+            state.pending = true;
+            PENDING
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(self);
+        }
+    }
+
+    fn summary(&self, suppressed: u64) -> Option<Log> {
+        let message: Cow<'static, str> = match suppressed {
+            0 => return None,
+            1 => "suppressed 1 similar message".into(),
+            _ => format!("suppressed {suppressed} similar messages").into(),
+        };
+
+        Some(Log::new(self.level, message).caller(self.caller))
+    }
+}
+
+/// Outputs the summaries of the call sites whose suppressed logs were not summarized yet, such
+/// as at the end of a burst.
+pub fn report() {
+    for log in pending() {
+        log.output();
+    }
+}
+
+/// Takes the summaries of the call sites whose suppressed logs were not summarized yet.
+fn pending() -> Vec<Log> {
+    let pending = mem::take(&mut *PENDING.lock().unwrap_or_else(PoisonError::into_inner));
+
+    pending
+        .into_iter()
+        .filter_map(|call_site| {
+            let suppressed = {
+                let mut state = call_site.state();
+                state.pending = false;
+                mem::take(&mut state.suppressed)
+            };
+
+            let log = call_site.summary(suppressed)?;
+            let Caller { file, line, .. } = call_site.caller;
+            let message = format!("{} from {file}:{line}", log.message);
+
+            Some(log.message(message))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Serializes the tests, since [`report`] takes the pending summaries of every call site.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    macro_rules! call_site {
+        () => {{
+            static CALL_SITE: CallSite = CallSite::new(
+                LogLevel::Warning,
+                Caller {
+                    module_path: module_path!(),
+                    file: file!(),
+                    line: line!(),
+                },
+            );
+            &CALL_SITE
+        }};
+    }
+
+    fn serial() -> MutexGuard<'static, ()> {
+        SERIAL.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[test]
+    fn every_counts_suppressed_calls() {
+        let _serial = serial();
+        let call_site = call_site!();
+        let period = Duration::from_millis(50);
+
+        assert_eq!(call_site.every(period), Some(0));
+        assert_eq!(call_site.every(period), None);
+        assert_eq!(call_site.every(period), None);
+
+        thread::sleep(period);
+        assert_eq!(call_site.every(period), Some(2));
+        assert_eq!(call_site.every(period), None);
+    }
+
+    #[test]
+    fn sampled_emits_one_in_n() {
+        let _serial = serial();
+        let call_site = call_site!();
+
+        let emitted = (0..7).map(|_| call_site.sampled(3)).collect::<Vec<_>>();
+        assert_eq!(emitted, [Some(0), None, None, Some(2), None, None, Some(2)]);
+
+        let every_call = call_site!();
+        assert!((0..3).all(|_| every_call.sampled(0) == Some(0)));
+    }
+
+    #[test]
+    fn summarizes_suppressed_calls() {
+        let call_site = call_site!();
+
+        assert!(call_site.summary(0).is_none());
+        assert_eq!(
+            call_site.summary(1).map(|log| log.message),
+            Some("suppressed 1 similar message".into())
+        );
+        assert_eq!(
+            call_site.summary(3).map(|log| log.message),
+            Some("suppressed 3 similar messages".into())
+        );
+    }
+
+    #[test]
+    fn reports_pending_summaries_once() {
+        let _serial = serial();
+        let call_site = call_site!();
+
+        assert_eq!(call_site.sampled(10), Some(0));
+        assert_eq!(call_site.sampled(10), None);
+        assert_eq!(call_site.sampled(10), None);
+
+        // Other tests leave their own call sites pending.
+        let summaries = || {
+            let suffix = format!(" from {}:{}", file!(), call_site.caller.line);
+            pending()
+                .into_iter()
+                .filter(|log| log.message.ends_with(&suffix))
+                .map(|log| log.message.replace(&suffix, ""))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(summaries(), ["suppressed 2 similar messages"]);
+        assert!(summaries().is_empty());
+
+        // The count was reset by the report, and the call site can be reported again.
+        assert_eq!(call_site.sampled(10), None);
+        assert_eq!(summaries(), ["suppressed 1 similar message"]);
+    }
+}
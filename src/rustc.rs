@@ -0,0 +1,439 @@
+//! Conversion between logs and the JSON diagnostics emitted by rustc and cargo with
+//! `--message-format=json`.
+
+use crate::{
+    json::{self, Value},
+    location::Section,
+    progress,
+    sink::Sink,
+    utils, Location, Log, LogLevel,
+};
+use std::io::{self, Write};
+
+/// Parses a line of cargo or rustc JSON output.
+///
+/// Returns `None` for messages that are not diagnostics, like cargo's `compiler-artifact`.
+///
+/// # Errors
+/// Returns an error if the line is not valid JSON.
+pub fn parse(line: &str) -> Result<Option<Log>, json::Error> {
+    let value: Value = line.parse()?;
+
+    let diagnostic = match value.get("reason").and_then(Value::as_str) {
+        Some("compiler-message") => value.get("message"),
+        Some(_) => None,
+        None => Some(&value),
+    };
+
+    let Some(mut log) = diagnostic.and_then(from_value) else {
+        return Ok(None);
+    };
+
+    if let Some(target) = value
+        .get("target")
+        .and_then(|target| target.get("name"))
+        .and_then(Value::as_str)
+    {
+        log = log.target(target);
+    }
+
+    Ok(Some(log))
+}
+
+/// Converts a rustc diagnostic into a log.
+///
+/// The first primary span becomes the location and its label the label of the log, the other
+/// spans become related locations. Children with a suggested replacement become suggestions, and children without
+/// a span are added to the hint.
+pub fn from_value(diagnostic: &Value) -> Option<Log> {
+    if diagnostic
+        .get("$message_type")
+        .and_then(Value::as_str)
+        .is_some_and(|kind| kind != "diagnostic")
+    {
+        return None;
+    }
+
+    let message = diagnostic.get("message")?.as_str()?;
+    let mut log = Log::new(level(diagnostic), message.to_string());
+
+    if let Some(code) = diagnostic
+        .get("code")
+        .and_then(|code| code.get("code"))
+        .and_then(Value::as_str)
+    {
+        log = log.code(code);
+    }
+
+    for span in spans(diagnostic) {
+        let Some(location) = location(span) else {
+            continue;
+        };
+        let label = span.get("label").and_then(Value::as_str);
+
+        if log.location.is_none() && span.get("is_primary") == Some(&Value::Bool(true)) {
+            if let Some(label) = label {
+                log = log.label(label);
+            }
+            log = log.location(location);
+        } else {
+            log = log.related(location, label.unwrap_or_default());
+        }
+    }
+
+    let mut hints = Vec::new();
+    for child in array(diagnostic.get("children")) {
+        let Some(message) = child.get("message").and_then(Value::as_str) else {
+            continue;
+        };
+        let level = child.get("level").and_then(Value::as_str).unwrap_or("note");
+
+        if spans(child).is_empty() {
+            hints.push(if level == "help" {
+                message.to_string()
+            } else {
+                format!("{level}: {message}")
+            });
+        }
+
+        for span in spans(child) {
+            let Some(location) = location(span) else {
+                continue;
+            };
+
+            match span.get("suggested_replacement").and_then(Value::as_str) {
+                Some(replacement) => log = log.suggestion(location, replacement, message),
+                None => log = log.related(location, message),
+            }
+        }
+    }
+
+    if !hints.is_empty() {
+        log = log.hint(hints.join("\n"));
+    }
+
+    Some(log)
+}
+
+/// Converts a log into a rustc diagnostic.
+///
+/// The label becomes the label of the primary span, related locations become spans labelled
+/// with their message, suggestions become `help` children with a suggested replacement, and
+/// causes become `note` children.
+pub fn to_value(log: &Log) -> Value {
+    let level = match log.level {
+        LogLevel::Error | LogLevel::Fatal => "error",
+        LogLevel::Warning => "warning",
+        _ => "note",
+    };
+
+    let spans = log
+        .location
+        .iter()
+        .filter_map(|location| span(location, true, log.label.as_deref(), None))
+        .chain(
+            log.related
+                .iter()
+                .filter_map(|related| span(&related.location, false, Some(&related.message), None)),
+        )
+        .collect();
+
+    let mut children = log
+        .suggestions
+        .iter()
+        .map(|suggestion| {
+            let span = span(
+                &suggestion.location,
+                true,
+                None,
+                Some(&suggestion.replacement),
+            );
+            child("help", &suggestion.message, span.into_iter().collect())
+        })
+        .collect::<Vec<_>>();
+
+    children.extend(
+        log.causes
+            .iter()
+            .map(|cause| child("note", &format!("caused by: {cause}"), Vec::new())),
+    );
+    children.extend(log.hint.iter().map(|hint| child("help", hint, Vec::new())));
+
+    json::object([
+        ("$message_type", "diagnostic".into()),
+        ("message", log.message.as_ref().into()),
+        (
+            "code",
+            log.code
+                .as_ref()
+                .map(|code| json::object([("code", code.as_str().into())]))
+                .into(),
+        ),
+        ("level", level.into()),
+        ("spans", Value::Array(spans)),
+        ("children", Value::Array(children)),
+        ("rendered", utils::strip_ansi(&log.to_string()).into()),
+    ])
+}
+
+/// Writes every log to stdout as a rustc JSON diagnostic, one per line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rustc;
+
+impl Sink for Rustc {
+    fn write(&self, log: &Log) {
+        let diagnostic = to_value(log);
+        progress::suspend(|| println!("{diagnostic}"));
+    }
+
+    fn flush(&self) {
+        let _ = io::stdout().flush();
+    }
+}
+
+fn level(diagnostic: &Value) -> LogLevel {
+    match diagnostic.get("level").and_then(Value::as_str) {
+        Some("error: internal compiler error") => LogLevel::Fatal,
+        Some("error") => LogLevel::Error,
+        Some("warning") => LogLevel::Warning,
+        Some("help") => LogLevel::Info,
+        _ => LogLevel::Note,
+    }
+}
+
+fn spans(diagnostic: &Value) -> &[Value] {
+    array(diagnostic.get("spans"))
+}
+
+/// Elements of an array, an empty slice for any other value.
+fn array(value: Option<&Value>) -> &[Value] {
+    value.and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn number(value: &Value, key: &str) -> Option<usize> {
+    usize::try_from(value.get(key)?.as_u64()?).ok()
+}
+
+fn child(level: &str, message: &str, spans: Vec<Value>) -> Value {
+    json::object([
+        ("message", message.into()),
+        ("level", level.into()),
+        ("spans", Value::Array(spans)),
+        ("children", Value::Array(Vec::new())),
+    ])
+}
+
+/// Reads the file of a span, falling back to the lines included in the span if the file is
+/// not readable, so the snippet can still be shown.
+fn location(span: &Value) -> Option<Location> {
+    let path = span.get("file_name")?.as_str()?;
+    let line_start = number(span, "line_start")?.saturating_sub(1);
+    let line_end = number(span, "line_end")?.saturating_sub(1);
+    let col_start = number(span, "column_start")?.saturating_sub(1);
+    let col_end = number(span, "column_end")?.saturating_sub(1);
+
+    let location = Location::from_path(path).unwrap_or_else(|_| {
+        let lines = array(span.get("text"))
+            .iter()
+            .filter_map(|line| line.get("text")?.as_str())
+            .collect::<Vec<_>>();
+
+        Location::from_text("\n".repeat(line_start) + &lines.join("\n")).path(path)
+    });
+
+    // rustc counts columns in characters, sections in bytes.
+    let section = {
+        let text = location.text().unwrap_or_default();
+        let lines = text.lines().collect::<Vec<_>>();
+        let byte_column = |line: usize, col: usize| {
+            lines.get(line).map_or(col, |text| {
+                text.char_indices()
+                    .nth(col)
+                    .map_or(text.len(), |(idx, _)| idx)
+            })
+        };
+
+        Section::new(
+            line_start..=line_end,
+            byte_column(line_start, col_start)..=byte_column(line_end, col_end),
+        )
+    };
+
+    Some(location.section(section))
+}
+
+fn span(
+    location: &Location,
+    is_primary: bool,
+    label: Option<&str>,
+    replacement: Option<&str>,
+) -> Option<Value> {
+    let path = location.path.as_ref()?;
+    let section = location
+        .section
+        .clone()
+        .unwrap_or(Section::new(0..=0, 0..=0));
+
+    let text = location.text().unwrap_or_default();
+    let lines = text.lines().collect::<Vec<_>>();
+    let char_column = |line: usize, col: usize| {
+        lines.get(line).map_or(col, |text| {
+            text.get(..col.min(text.len()))
+                .map_or(col, |prefix| prefix.chars().count())
+        })
+    };
+    let byte_offset = |line: usize, col: usize| {
+        text.split_inclusive('\n')
+            .take(line)
+            .map(str::len)
+            .sum::<usize>()
+            + col
+    };
+
+    let (line_start, col_start) = (*section.lines().start(), *section.cols().start());
+    let (line_end, col_end) = (*section.lines().end(), *section.cols().end());
+
+    let text = (line_start..=line_end)
+        .filter_map(|line| {
+            let text = lines.get(line)?;
+            let start = if line == line_start { col_start } else { 0 };
+            let end = if line == line_end {
+                col_end
+            } else {
+                text.len()
+            };
+
+            Some(json::object([
+                ("text", (*text).into()),
+                ("highlight_start", (char_column(line, start) + 1).into()),
+                ("highlight_end", (char_column(line, end) + 1).into()),
+            ]))
+        })
+        .collect();
+
+    Some(json::object([
+        ("file_name", path.display().to_string().into()),
+        ("byte_start", byte_offset(line_start, col_start).into()),
+        ("byte_end", byte_offset(line_end, col_end).into()),
+        ("line_start", (line_start + 1).into()),
+        ("line_end", (line_end + 1).into()),
+        (
+            "column_start",
+            (char_column(line_start, col_start) + 1).into(),
+        ),
+        ("column_end", (char_column(line_end, col_end) + 1).into()),
+        ("is_primary", is_primary.into()),
+        ("text", Value::Array(text)),
+        ("label", label.into()),
+        ("suggested_replacement", replacement.into()),
+        (
+            "suggestion_applicability",
+            replacement.map(|_| "MaybeIncorrect").into(),
+        ),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `let x: u32 = "a";`
+    const MISMATCHED_TYPES: &str = r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"Expected type did not match the received type.\n\nErroneous code examples:\n\n```compile_fail,E0308\nfn plus_one(x: i32) -> i32 {\n    x + 1\n}\n\nplus_one(\"Not a number\");\n//       ^^^^^^^^^^^^^^ expected `i32`, found `&str`\n\nif \"Not a bool\" {\n// ^^^^^^^^^^^^ expected `bool`, found `&str`\n}\n\nlet x: f32 = \"Not a float\";\n//     ---   ^^^^^^^^^^^^^ expected `f32`, found `&str`\n//     |\n//     expected due to this\n```\n\nThis error occurs when an expression was used in a place where the compiler\nexpected an expression of a different type. It can occur in several cases, the\nmost common being when calling a function and passing an argument which has a\ndifferent type than the matching type in the function declaration.\n"},"level":"error","spans":[{"file_name":"t.rs","byte_start":25,"byte_end":28,"line_start":1,"line_end":1,"column_start":26,"column_end":29,"is_primary":true,"text":[{"text":"fn main() { let x: u32 = \"a\"; }","highlight_start":26,"highlight_end":29}],"label":"expected `u32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null},{"file_name":"t.rs","byte_start":19,"byte_end":22,"line_start":1,"line_end":1,"column_start":20,"column_end":23,"is_primary":false,"text":[{"text":"fn main() { let x: u32 = \"a\"; }","highlight_start":20,"highlight_end":23}],"label":"expected due to this","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"error[E0308]: mismatched types\n --> t.rs:1:26\n  |\n1 | fn main() { let x: u32 = \"a\"; }\n  |                    ---   ^^^ expected `u32`, found `&str`\n  |                    |\n  |                    expected due to this\n\n"}"#;
+
+    /// `let café = 1;`, on the second line.
+    const UNUSED_VARIABLE: &str = r#"{"$message_type":"diagnostic","message":"unused variable: `café`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"u.rs","byte_start":20,"byte_end":25,"line_start":2,"line_end":2,"column_start":9,"column_end":13,"is_primary":true,"text":[{"text":"    let café = 1;","highlight_start":9,"highlight_end":13}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"`#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default","code":null,"level":"note","spans":[],"children":[],"rendered":null},{"message":"if this is intentional, prefix it with an underscore","code":null,"level":"help","spans":[{"file_name":"u.rs","byte_start":20,"byte_end":25,"line_start":2,"line_end":2,"column_start":9,"column_end":13,"is_primary":true,"text":[{"text":"    let café = 1;","highlight_start":9,"highlight_end":13}],"label":null,"suggested_replacement":"_café","suggestion_applicability":"MachineApplicable","expansion":null}],"children":[],"rendered":null}],"rendered":"warning: unused variable: `café`\n --> u.rs:2:9\n  |\n2 |     let café = 1;\n  |         ^^^^ help: if this is intentional, prefix it with an underscore: `_café`\n  |\n  = note: `#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default\n\n"}"#;
+
+    fn parse(line: &str) -> Log {
+        super::parse(line)
+            .expect("The line is valid JSON")
+            .expect("The line is a diagnostic")
+    }
+
+    #[test]
+    fn primary_span_becomes_location_and_label() {
+        let log = parse(MISMATCHED_TYPES);
+
+        assert_eq!(log.level, LogLevel::Error);
+        assert_eq!(log.message, "mismatched types");
+        assert_eq!(log.code.as_deref(), Some("E0308"));
+
+        let location = log.location.as_ref().expect("The log has a location");
+        assert_eq!(location.path.as_deref(), Some("t.rs".as_ref()));
+        assert_eq!(location.section, Some(Section::new(0..=0, 25..=28)));
+        assert_eq!(log.label.as_deref(), Some("expected `u32`, found `&str`"));
+
+        assert_eq!(log.related.len(), 1);
+        assert_eq!(log.related[0].message, "expected due to this");
+        assert_eq!(
+            log.related[0].location.section,
+            Some(Section::new(0..=0, 19..=22))
+        );
+    }
+
+    #[test]
+    fn children_become_suggestions_and_hint() {
+        let log = parse(UNUSED_VARIABLE);
+
+        assert_eq!(log.level, LogLevel::Warning);
+        assert_eq!(
+            log.hint.as_deref(),
+            Some("note: `#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default")
+        );
+        assert!(log.related.is_empty());
+
+        assert_eq!(log.suggestions.len(), 1);
+        assert_eq!(log.suggestions[0].replacement, "_café");
+        assert_eq!(
+            log.suggestions[0].message,
+            "if this is intentional, prefix it with an underscore"
+        );
+    }
+
+    #[test]
+    fn converts_character_columns_to_bytes() {
+        let log = parse(UNUSED_VARIABLE);
+
+        // `café` spans characters 8..12, and bytes 8..13.
+        let section = Some(Section::new(1..=1, 8..=13));
+        assert_eq!(
+            log.location.expect("The log has a location").section,
+            section
+        );
+        assert_eq!(log.suggestions[0].location.section, section);
+
+        let span = &to_value(&parse(UNUSED_VARIABLE))["spans"][0];
+        assert_eq!(span["column_start"], 9);
+        assert_eq!(span["column_end"], 13);
+    }
+
+    #[test]
+    fn unwraps_cargo_messages() {
+        let line = format!(
+            r#"{{"reason":"compiler-message","target":{{"name":"app"}},"message":{MISMATCHED_TYPES}}}"#
+        );
+        assert_eq!(parse(&line).target.as_deref(), Some("app"));
+
+        let artifact = r#"{"reason":"compiler-artifact","target":{"name":"app"}}"#;
+        assert!(super::parse(artifact)
+            .expect("The line is valid JSON")
+            .is_none());
+        assert!(super::parse("error: not JSON").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        for line in [MISMATCHED_TYPES, UNUSED_VARIABLE] {
+            let log = parse(line);
+            let parsed = parse(&to_value(&log).to_string());
+
+            assert_eq!(parsed.level, log.level);
+            assert_eq!(parsed.message, log.message);
+            assert_eq!(parsed.code, log.code);
+            assert_eq!(parsed.location, log.location);
+            assert_eq!(parsed.label, log.label);
+            assert_eq!(parsed.hint, log.hint);
+            assert_eq!(parsed.related, log.related);
+            assert_eq!(parsed.suggestions, log.suggestions);
+        }
+    }
+}
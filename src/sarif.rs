@@ -0,0 +1,272 @@
+//! Export of logs as a SARIF 2.1.0 document, as used by code scanning dashboards.
+
+use crate::{
+    json::{self, Value},
+    sink::Sink,
+    utils, Location, Log, LogLevel,
+};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A single SARIF run, built up from logs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    tool: String,
+    version: Option<String>,
+    /// Codes of the logs with the rule describing them, in order of appearance.
+    rules: Vec<(String, Value)>,
+    results: Vec<Value>,
+}
+
+impl Report {
+    pub fn new(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            version: None,
+            rules: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Adds a log as a result, with its hint appended to the message. Logs with a code also add
+    /// a rule for that code, which gets the hint of the first log with that code as its help
+    /// text.
+    pub fn add(&mut self, log: &Log) {
+        let mut message = log.message.to_string();
+
+        let rule_index = log.code.as_ref().map(|code| {
+            self.rules
+                .iter()
+                .position(|(id, _)| id == code)
+                .unwrap_or_else(|| {
+                    self.rules.push((code.clone(), rule(code, log)));
+                    self.rules.len() - 1
+                })
+        });
+
+        if let Some(hint) = &log.hint {
+            message.push_str("\nhelp: ");
+            message.push_str(hint);
+        }
+
+        let level = match log.level {
+            LogLevel::Trace | LogLevel::Debug => "none",
+            LogLevel::Info | LogLevel::Note | LogLevel::Success => "note",
+            LogLevel::Warning => "warning",
+            LogLevel::Error | LogLevel::Fatal => "error",
+        };
+
+        let related = log
+            .related
+            .iter()
+            .enumerate()
+            .filter_map(|(id, related)| {
+                let mut location = physical_location(&related.location)?;
+                location["id"] = id.into();
+                location["message"] = text(&related.message);
+
+                Some(location)
+            })
+            .collect::<Vec<_>>();
+
+        let fixes = log
+            .suggestions
+            .iter()
+            .filter_map(|suggestion| {
+                let uri = uri(&suggestion.location)?;
+
+                Some(json::object([
+                    ("description", text(&suggestion.message)),
+                    (
+                        "artifactChanges",
+                        Value::Array(vec![json::object([
+                            ("artifactLocation", json::object([("uri", uri.into())])),
+                            (
+                                "replacements",
+                                Value::Array(vec![json::object([
+                                    ("deletedRegion", region(&suggestion.location)?),
+                                    ("insertedContent", text(&suggestion.replacement)),
+                                ])]),
+                            ),
+                        ])]),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        self.results.push(json::object([
+            ("ruleId", log.code.clone().into()),
+            ("ruleIndex", rule_index.into()),
+            ("level", level.into()),
+            ("message", text(&message)),
+            (
+                "locations",
+                non_empty(
+                    log.location
+                        .iter()
+                        .filter_map(physical_location)
+                        .map(|location| json::object([("physicalLocation", location)]))
+                        .collect(),
+                ),
+            ),
+            ("relatedLocations", non_empty(related)),
+            ("fixes", non_empty(fixes)),
+        ]));
+    }
+
+    pub fn to_value(&self) -> Value {
+        let rules = self.rules.iter().map(|(_, rule)| rule.clone()).collect();
+
+        let driver = json::object([
+            ("name", self.tool.clone().into()),
+            ("version", self.version.clone().into()),
+            ("rules", non_empty(rules)),
+        ]);
+
+        json::object([
+            ("$schema", SCHEMA.into()),
+            ("version", "2.1.0".into()),
+            (
+                "runs",
+                Value::Array(vec![json::object([
+                    ("tool", json::object([("driver", driver)])),
+                    ("results", Value::Array(self.results.clone())),
+                ])]),
+            ),
+        ])
+    }
+}
+
+/// Collects every log into a SARIF report.
+///
+/// The report is written when the sinks are [flushed](crate::sink::flush), which
+/// [`fatal::exit`](crate::fatal::exit) does, so it has to be flushed before exiting otherwise.
+#[derive(Debug)]
+pub struct Sarif {
+    path: PathBuf,
+    report: Mutex<Report>,
+}
+
+impl Sarif {
+    pub fn new(path: impl Into<PathBuf>, report: Report) -> Self {
+        Self {
+            path: path.into(),
+            report: Mutex::new(report),
+        }
+    }
+}
+
+impl Sink for Sarif {
+    fn write(&self, log: &Log) {
+        self.report
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .add(log);
+    }
+
+    fn flush(&self) {
+        let report = self.report.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = fs::write(&self.path, report.to_value().to_string());
+    }
+}
+
+fn rule(code: &str, log: &Log) -> Value {
+    json::object([
+        ("id", code.into()),
+        ("help", log.hint.as_deref().map(text).into()),
+    ])
+}
+
+fn text(text: &str) -> Value {
+    json::object([("text", text.into())])
+}
+
+fn non_empty(values: Vec<Value>) -> Value {
+    if values.is_empty() {
+        Value::Null
+    } else {
+        Value::Array(values)
+    }
+}
+
+fn uri(location: &Location) -> Option<String> {
+    let path = location.path.as_ref()?;
+    Some(path.display().to_string().replace('\\', "/"))
+}
+
+/// 1-based region of a location, with an exclusive end column. Columns are counted in UTF-16
+/// code units, the default `columnKind` of SARIF.
+fn region(location: &Location) -> Option<Value> {
+    let section = location.section.as_ref()?;
+
+    let text = location.text().unwrap_or_default();
+    let lines = text.lines().collect::<Vec<_>>();
+    let column = |line: usize, col: usize| {
+        lines
+            .get(line)
+            .map_or(col, |text| utils::utf16_column(text, col))
+            + 1
+    };
+
+    Some(json::object([
+        ("startLine", (section.lines().start() + 1).into()),
+        (
+            "startColumn",
+            column(*section.lines().start(), *section.cols().start()).into(),
+        ),
+        ("endLine", (section.lines().end() + 1).into()),
+        (
+            "endColumn",
+            column(*section.lines().end(), *section.cols().end()).into(),
+        ),
+    ]))
+}
+
+fn physical_location(location: &Location) -> Option<Value> {
+    Some(json::object([
+        (
+            "artifactLocation",
+            json::object([("uri", uri(location)?.into())]),
+        ),
+        ("region", region(location).into()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Section;
+
+    #[test]
+    fn counts_columns_in_utf16() {
+        let location = Location::from_text("let café = 1;")
+            .path("src/main.rs")
+            .section(Section::new(0..=0, 4..=9));
+
+        let log = Log::new(LogLevel::Warning, "unused variable")
+            .location(location.clone())
+            .suggestion(location, "_café", "prefix it with an underscore");
+
+        let mut report = Report::new("checker");
+        report.add(&log);
+
+        let result = &report.to_value()["runs"][0]["results"][0];
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        let deleted = &result["fixes"][0]["artifactChanges"][0]["replacements"][0]["deletedRegion"];
+
+        for region in [region, deleted] {
+            assert_eq!(region["startColumn"], 5);
+            assert_eq!(region["endColumn"], 9);
+        }
+    }
+}
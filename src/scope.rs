@@ -0,0 +1,66 @@
+//! Thread-local stack of contextual scopes that are attached to every log created inside them.
+
+use crate::stack::StackGuard;
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+};
+
+thread_local! {
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub name: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        if !self.fields.is_empty() {
+            let fields = self
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(f, "{{{fields}}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pops its scope, and any scope pushed after it, when dropped.
+#[must_use = "the scope is popped as soon as the guard is dropped"]
+pub struct ScopeGuard(StackGuard<Scope>);
+
+impl ScopeGuard {
+    /// Attaches a key-value field to the scope.
+    pub fn field(self, key: impl Into<String>, value: impl Display) -> Self {
+        self.0
+            .with(|scope| scope.fields.push((key.into(), value.to_string())));
+
+        self
+    }
+}
+
+/// Pushes a scope that stays active until the returned guard is dropped.
+pub fn scope(name: impl Into<String>) -> ScopeGuard {
+    ScopeGuard(StackGuard::push(
+        &SCOPES,
+        Scope {
+            name: name.into(),
+            fields: Vec::new(),
+        },
+    ))
+}
+
+/// Returns the active scopes of the current thread, outermost first.
+pub fn current() -> Vec<Scope> {
+    SCOPES.with_borrow(Clone::clone)
+}
@@ -0,0 +1,87 @@
+//! Destinations that logs are written to by [`Log::output`].
+
+use crate::{progress, ratelimit, Log};
+use std::{
+    io::{self, Write},
+    sync::{LazyLock, PoisonError, RwLock},
+};
+
+/// A destination for logs.
+pub trait Sink: Send + Sync {
+    fn write(&self, log: &Log);
+
+    fn flush(&self) {}
+}
+
+/// Writes logs to stdout, this is the default sink.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stdout;
+
+impl Sink for Stdout {
+    fn write(&self, log: &Log) {
+        progress::suspend(|| print!("{log}"));
+    }
+
+    fn flush(&self) {
+        let _ = io::stdout().flush();
+    }
+}
+
+/// Writes logs to stderr.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stderr;
+
+impl Sink for Stderr {
+    fn write(&self, log: &Log) {
+        progress::suspend(|| eprint!("{log}"));
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
+
+/// Starts with [`Stdout`], and the annotation sink of the CI system if it has one. With the
+/// `log_facade` feature, it starts with [`LogFacade`](crate::LogFacade) instead.
+static SINKS: LazyLock<RwLock<Vec<Box<dyn Sink>>>> = LazyLock::new(|| {
+    #[cfg(feature = "log_facade")]
+    let sinks: Vec<Box<dyn Sink>> = vec![Box::new(crate::LogFacade)];
+
+    #[cfg(not(feature = "log_facade"))]
+    let sinks = {
+        let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(Stdout)];
+        sinks.extend(crate::ci::Provider::detect().and_then(crate::ci::Provider::sink));
+        sinks
+    };
+
+    RwLock::new(sinks)
+});
+
+/// Adds a sink that receives every log alongside the existing ones.
+pub fn add(sink: impl Sink + 'static) {
+    SINKS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(sink));
+}
+
+/// Replaces all sinks, an empty list discards every log.
+pub fn set(sinks: Vec<Box<dyn Sink>>) {
+    *SINKS.write().unwrap_or_else(PoisonError::into_inner) = sinks;
+}
+
+/// Writes a log to every sink.
+pub fn write(log: &Log) {
+    for sink in SINKS.read().unwrap_or_else(PoisonError::into_inner).iter() {
+        sink.write(log);
+    }
+}
+
+/// Outputs the pending [`ratelimit`] summaries, and flushes every sink.
+pub fn flush() {
+    ratelimit::report();
+
+    for sink in SINKS.read().unwrap_or_else(PoisonError::into_inner).iter() {
+        sink.flush();
+    }
+}
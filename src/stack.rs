@@ -0,0 +1,47 @@
+//! Guard of an entry in a thread-local stack, shared by groups, scopes and captures.
+
+use std::{cell::RefCell, marker::PhantomData, thread::LocalKey};
+
+pub(crate) type Stack<T> = LocalKey<RefCell<Vec<T>>>;
+
+/// Pops its entry, and any entry pushed after it, when dropped.
+pub(crate) struct StackGuard<T: 'static> {
+    stack: &'static Stack<T>,
+    depth: usize,
+    // The stack is thread-local, so the guard must stay on the thread that pushed the entry.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T> StackGuard<T> {
+    pub(crate) fn push(stack: &'static Stack<T>, entry: T) -> Self {
+        stack.with_borrow_mut(|entries| {
+            entries.push(entry);
+
+            Self {
+                stack,
+                depth: entries.len() - 1,
+                _not_send: PhantomData,
+            }
+        })
+    }
+
+    /// Calls `f` with the entry, unless it was already popped.
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.stack
+            .with_borrow_mut(|entries| entries.get_mut(self.depth).map(f))
+    }
+
+    /// Pops the entry and the entries pushed after it, outermost first.
+    pub(crate) fn pop(&self) -> Vec<T> {
+        self.stack.with_borrow_mut(|entries| {
+            let depth = self.depth.min(entries.len());
+            entries.split_off(depth)
+        })
+    }
+}
+
+impl<T> Drop for StackGuard<T> {
+    fn drop(&mut self) {
+        self.pop();
+    }
+}
@@ -0,0 +1,305 @@
+//! Sink sending logs to a syslog daemon in the RFC 5424 format.
+
+use crate::{sink::Sink, utils, Log, LogLevel};
+use std::{
+    fs, io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixDatagram, path::PathBuf};
+
+/// Socket of the local syslog daemon.
+#[cfg(unix)]
+pub const DEFAULT_SOCKET: &str = "/dev/log";
+
+/// Identifier of the structured data element holding the fields of a log, under the
+/// enterprise number reserved for documentation.
+const SD_ID: &str = "logger@32473";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Facility {
+    Kernel = 0,
+    #[default]
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// Syslog severity of a level, also used as the journald `PRIORITY`.
+pub fn severity(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Fatal => 2,
+        LogLevel::Error => 3,
+        LogLevel::Warning => 4,
+        LogLevel::Note => 5,
+        LogLevel::Info | LogLevel::Success => 6,
+        LogLevel::Trace | LogLevel::Debug => 7,
+    }
+}
+
+#[derive(Debug)]
+enum Socket {
+    #[cfg(unix)]
+    Unix(UnixDatagram, PathBuf),
+    Udp(UdpSocket),
+}
+
+/// Sends every log as an RFC 5424 message over a Unix datagram socket or UDP.
+#[derive(Debug)]
+pub struct Syslog {
+    socket: Socket,
+    facility: Facility,
+    hostname: String,
+    app_name: String,
+}
+
+impl Syslog {
+    /// Sends logs to the local syslog daemon at [`DEFAULT_SOCKET`].
+    ///
+    /// # Errors
+    /// This function will propagate errors from creating the socket.
+    #[cfg(unix)]
+    pub fn local() -> io::Result<Self> {
+        Self::unix(DEFAULT_SOCKET)
+    }
+
+    /// # Errors
+    /// This function will propagate errors from creating the socket.
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Ok(Self::new(Socket::Unix(
+            UnixDatagram::unbound()?,
+            path.into(),
+        )))
+    }
+
+    /// # Errors
+    /// This function will propagate errors from resolving the address and creating the socket.
+    pub fn udp(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to send to"))?;
+
+        let local: SocketAddr = if address.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0; 16], 0).into()
+        };
+
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(address)?;
+
+        Ok(Self::new(Socket::Udp(socket)))
+    }
+
+    fn new(socket: Socket) -> Self {
+        let hostname = fs::read_to_string("/proc/sys/kernel/hostname")
+            .map(|hostname| hostname.trim().to_string())
+            .unwrap_or_default();
+
+        let app_name = utils::app_name();
+
+        Self {
+            socket,
+            facility: Facility::default(),
+            hostname: header_field(&hostname, 255),
+            app_name: header_field(app_name.as_deref().unwrap_or_default(), 48),
+        }
+    }
+
+    pub fn facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Formats the RFC 5424 message for a log.
+    pub fn format(&self, log: &Log) -> String {
+        let priority = self.facility as u8 * 8 + severity(log.level);
+
+        let mut params = Vec::new();
+        if let Some(location) = &log.location
+            && let Some(path) = &location.path
+        {
+            params.push(("file", path.display().to_string()));
+
+            if let Some(section) = &location.section {
+                params.push(("line", (section.lines().start() + 1).to_string()));
+            }
+        }
+        params.extend(log.target.clone().map(|target| ("target", target)));
+        params.extend(log.hint.clone().map(|hint| ("hint", hint)));
+        params.extend(log.causes.iter().map(|cause| ("cause", cause.clone())));
+
+        let structured_data = if params.is_empty() {
+            "-".to_string()
+        } else {
+            let params = params
+                .iter()
+                .map(|(name, value)| format!(" {name}=\"{}\"", escape_param(value)))
+                .collect::<Vec<_>>()
+                .concat();
+
+            format!("[{SD_ID}{params}]")
+        };
+
+        format!(
+            "<{priority}>1 {} {} {} {} {} {structured_data} \u{feff}{}",
+            timestamp(),
+            self.hostname,
+            self.app_name,
+            process::id(),
+            header_field(log.code.as_deref().unwrap_or_default(), 32),
+            log.message
+        )
+    }
+}
+
+impl Sink for Syslog {
+    fn write(&self, log: &Log) {
+        let message = self.format(log);
+
+        let _ = match &self.socket {
+            #[cfg(unix)]
+            Socket::Unix(socket, path) => socket.send_to(message.as_bytes(), path),
+            Socket::Udp(socket) => socket.send(message.as_bytes()),
+        };
+    }
+}
+
+/// Header fields are printable ASCII without spaces, or `-` when empty.
+fn header_field(value: &str, max_len: usize) -> String {
+    let value = value
+        .chars()
+        .filter(char::is_ascii_graphic)
+        .take(max_len)
+        .collect::<String>();
+
+    if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    }
+}
+
+fn escape_param(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+/// Current UTC time with millisecond precision, such as `2024-05-01T13:45:10.123Z`.
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = now.as_secs();
+    let (year, month, day) = utils::civil_date(seconds / 86400);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+        now.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, str, time::Duration};
+
+    fn receive(socket: &UdpSocket) -> String {
+        let mut buffer = [0; 4096];
+        let len = socket.recv(&mut buffer).unwrap();
+        str::from_utf8(&buffer[..len]).unwrap().to_string()
+    }
+
+    #[test]
+    fn sends_rfc5424_over_udp() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let syslog = Syslog::udp(listener.local_addr().unwrap())
+            .unwrap()
+            .facility(Facility::Local0);
+        let log = Log::new(LogLevel::Warning, "disk almost full")
+            .code("W01")
+            .target("storage")
+            .hint("remove \"old\" files]");
+        syslog.write(&log);
+
+        let message = receive(&listener);
+        let fields = message.splitn(7, ' ').collect::<Vec<_>>();
+
+        assert_eq!(fields[0], "<132>1");
+        assert_eq!(fields[1].len(), "2024-05-01T13:45:10.123Z".len());
+        assert!(fields[1].ends_with('Z'));
+        assert_eq!(fields[4], process::id().to_string());
+        assert_eq!(fields[5], "W01");
+        assert_eq!(
+            fields[6],
+            "[logger@32473 target=\"storage\" hint=\"remove \\\"old\\\" files\\]\"] \u{feff}disk almost full"
+        );
+    }
+
+    #[test]
+    fn nil_structured_data_and_message_id() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let syslog = Syslog::udp(listener.local_addr().unwrap()).unwrap();
+        syslog.write(&Log::new(LogLevel::Error, "failed"));
+
+        let message = receive(&listener);
+        assert!(message.starts_with("<11>1 "));
+        assert!(message.ends_with(" - - \u{feff}failed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sends_over_unix_socket() {
+        let path = env::temp_dir().join(format!("logger-syslog-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let listener = UnixDatagram::bind(&path).unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        Syslog::unix(&path)
+            .unwrap()
+            .write(&Log::new(LogLevel::Debug, "connected"));
+
+        let mut buffer = [0; 4096];
+        let len = listener.recv(&mut buffer).unwrap();
+        let message = str::from_utf8(&buffer[..len]).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(message.starts_with("<15>1 "));
+        assert!(message.ends_with("\u{feff}connected"));
+    }
+}
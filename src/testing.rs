@@ -0,0 +1,283 @@
+//! Helpers for testing the logs a program emits.
+//!
+//! Logs are rendered without color and with a fixed app name, so the output of a test does
+//! not depend on the terminal or on [`set_app_name!`](crate::set_app_name) calls in other tests.
+//!
+//! ```ignore
+//! #[test]
+//! fn warns_about_missing_config() {
+//!     let _logs = logger::testing::init();
+//!     load_config();
+//!     logger::assert_logged!(level = Warning, contains "config");
+//! }
+//! ```
+
+use crate::{history, level, sink::Sink, stack::StackGuard, utils, Log, LogLevel};
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    sync::Once,
+};
+
+/// App name shown by [`render`].
+pub const APP_NAME: &str = "app";
+
+thread_local! {
+    static CAPTURES: RefCell<Vec<Vec<Log>>> = const { RefCell::new(Vec::new()) };
+}
+
+static INSTALL: Once = Once::new();
+
+/// Renders a log like its [`Display`] implementation, without color and with [`APP_NAME`].
+pub fn render(log: &Log) -> String {
+    struct Rendered<'a>(&'a Log);
+
+    impl Display for Rendered<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.render(f, APP_NAME)
+        }
+    }
+
+    utils::strip_ansi(&Rendered(log).to_string())
+}
+
+/// Records the logs of the thread that is capturing, see [`capture`].
+///
+/// It is added to the sinks by the first call to [`capture`] or [`init`], and has to be added
+/// again if the sinks are replaced with [`sink::set`](crate::sink::set) afterwards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Capture;
+
+impl Sink for Capture {
+    fn write(&self, log: &Log) {
+        CAPTURES.with_borrow_mut(|captures| {
+            if let Some(logs) = captures.last_mut() {
+                logs.push(log.clone());
+            }
+        });
+    }
+}
+
+/// Writes [`render`]ed logs with [`print!`], which the test harness captures for the test
+/// running on the current thread, including logs from threads spawned by the test.
+/// The output of a test is then only shown if it fails.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestOutput;
+
+impl Sink for TestOutput {
+    fn write(&self, log: &Log) {
+        print!("{}", render(log));
+    }
+}
+
+/// Stops capturing, along with any capture started after it, when dropped.
+#[must_use = "capturing stops as soon as the guard is dropped"]
+pub struct CaptureGuard(StackGuard<Vec<Log>>);
+
+impl CaptureGuard {
+    /// Returns the logs captured so far.
+    pub fn logs(&self) -> Vec<Log> {
+        self.0.with(|logs| logs.clone()).unwrap_or_default()
+    }
+
+    /// Returns the [`render`]ed logs captured so far.
+    pub fn rendered(&self) -> String {
+        self.logs().iter().map(render).collect()
+    }
+}
+
+/// Captures the logs emitted on the current thread until the returned guard is dropped.
+///
+/// Only logs that pass the level filter reach the sinks, and so are captured. A log is captured
+/// by the innermost capture only.
+pub fn capture() -> CaptureGuard {
+    INSTALL.call_once(|| crate::sink::add(Capture));
+
+    CaptureGuard(StackGuard::push(&CAPTURES, Vec::new()))
+}
+
+/// Sets up logging for a test, and captures the logs of the current thread until the returned
+/// guard is dropped.
+///
+/// Every call resets the configuration: the sinks, including the default stdout and CI sinks,
+/// are replaced with [`TestOutput`] and [`Capture`], every level is enabled and the
+/// [`history`](crate::history) is disabled. Changes made by an earlier test then don't leak into
+/// later ones, but the configuration is global, so tests running at the same time still share it.
+pub fn init() -> CaptureGuard {
+    INSTALL.call_once(|| {});
+    crate::sink::set(vec![Box::new(TestOutput), Box::new(Capture)]);
+    level::set_min_level(LogLevel::Trace);
+    history::disable();
+
+    capture()
+}
+
+/// Asserts that the innermost capture has a log of `level` whose rendered output contains
+/// `text`, used by [`assert_logged!`](crate::assert_logged).
+///
+/// # Panics
+/// Panics if there is no such log, or if no capture is active on the current thread.
+#[track_caller]
+pub fn assert_logged(level: Option<LogLevel>, text: Option<&str>) {
+    let (matched, rendered) = find(level, text);
+    assert!(
+        matched,
+        "no log matching {} was captured, captured logs:\n{rendered}",
+        describe(level, text)
+    );
+}
+
+/// Asserts that the innermost capture has no log of `level` whose rendered output contains
+/// `text`, used by [`assert_not_logged!`](crate::assert_not_logged).
+///
+/// # Panics
+/// Panics if there is such a log, or if no capture is active on the current thread.
+#[track_caller]
+pub fn assert_not_logged(level: Option<LogLevel>, text: Option<&str>) {
+    let (matched, rendered) = find(level, text);
+    assert!(
+        !matched,
+        "a log matching {} was captured, captured logs:\n{rendered}",
+        describe(level, text)
+    );
+}
+
+#[track_caller]
+fn find(level: Option<LogLevel>, text: Option<&str>) -> (bool, String) {
+    let logs = CAPTURES.with_borrow(|captures| captures.last().cloned());
+    let Some(logs) = logs else {
+        panic!("no capture is active on this thread, start one with `testing::capture`");
+    };
+
+    let matched = logs.iter().any(|log| {
+        level.is_none_or(|level| log.level == level)
+            && text.is_none_or(|text| render(log).contains(text))
+    });
+
+    (matched, logs.iter().map(render).collect())
+}
+
+fn describe(level: Option<LogLevel>, text: Option<&str>) -> String {
+    match (level, text) {
+        (Some(level), Some(text)) => format!("level `{level}` containing {text:?}"),
+        (Some(level), None) => format!("level `{level}`"),
+        (None, Some(text)) => format!("containing {text:?}"),
+        (None, None) => "anything".to_string(),
+    }
+}
+
+/// Asserts that the innermost [`capture`](crate::testing::capture) of the current thread
+/// has a matching log.
+///
+/// ```ignore
+/// assert_logged!(level = Warning, contains "config");
+/// assert_logged!(contains "config");
+/// assert_logged!(level = Error);
+/// ```
+#[macro_export]
+macro_rules! assert_logged {
+    (level = $level:ident, contains $text:expr $(,)?) => {
+        $crate::testing::assert_logged(Some($crate::LogLevel::$level), Some($text))
+    };
+    (level = $level:ident $(,)?) => {
+        $crate::testing::assert_logged(Some($crate::LogLevel::$level), None)
+    };
+    (contains $text:expr $(,)?) => {
+        $crate::testing::assert_logged(None, Some($text))
+    };
+}
+
+/// Asserts that the innermost [`capture`](crate::testing::capture) of the current thread
+/// has no matching log, takes the same arguments as [`assert_logged!`].
+#[macro_export]
+macro_rules! assert_not_logged {
+    (level = $level:ident, contains $text:expr $(,)?) => {
+        $crate::testing::assert_not_logged(Some($crate::LogLevel::$level), Some($text))
+    };
+    (level = $level:ident $(,)?) => {
+        $crate::testing::assert_not_logged(Some($crate::LogLevel::$level), None)
+    };
+    (contains $text:expr $(,)?) => {
+        $crate::testing::assert_not_logged(None, Some($text))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Written to the sink directly, so the tests do not depend on the `max_level_*` features.
+    fn log(level: LogLevel, message: &'static str) {
+        Capture.write(&Log::new(level, message));
+    }
+
+    #[test]
+    fn matches_level_and_text() {
+        let logs = capture();
+        log(LogLevel::Warning, "missing config");
+        log(LogLevel::Info, "loaded");
+
+        crate::assert_logged!(level = Warning, contains "config");
+        crate::assert_logged!(level = Info);
+        crate::assert_logged!(contains "loaded");
+        crate::assert_not_logged!(level = Error);
+        crate::assert_not_logged!(level = Info, contains "config");
+        crate::assert_not_logged!(contains "missing file");
+
+        assert_eq!(logs.logs().len(), 2);
+        assert!(logs.rendered().contains("warning[app]: missing config"));
+    }
+
+    #[test]
+    #[should_panic(expected = "no log matching level `error` containing \"config\"")]
+    fn fails_without_a_match() {
+        let _logs = capture();
+        log(LogLevel::Warning, "missing config");
+
+        crate::assert_logged!(level = Error, contains "config");
+    }
+
+    #[test]
+    #[should_panic(expected = "a log matching level `warning` was captured")]
+    fn fails_with_an_unexpected_match() {
+        let _logs = capture();
+        log(LogLevel::Warning, "missing config");
+
+        crate::assert_not_logged!(level = Warning);
+    }
+
+    #[test]
+    #[should_panic(expected = "no capture is active")]
+    fn fails_without_a_capture() {
+        crate::assert_logged!(contains "anything");
+    }
+
+    #[test]
+    fn innermost_capture_only() {
+        let outer = capture();
+        log(LogLevel::Info, "outer");
+
+        {
+            let inner = capture();
+            log(LogLevel::Info, "inner");
+
+            crate::assert_not_logged!(contains "outer");
+            assert_eq!(inner.logs().len(), 1);
+        }
+
+        crate::assert_logged!(contains "outer");
+        crate::assert_not_logged!(contains "inner");
+        assert_eq!(outer.logs().len(), 1);
+    }
+
+    #[test]
+    fn init_resets_the_configuration() {
+        let _logs = init();
+        level::set_min_level(LogLevel::Error);
+        history::enable(10);
+
+        let _logs = init();
+        assert_eq!(level::min_level(), LogLevel::Trace);
+        assert_eq!(history::capacity(), 0);
+    }
+}
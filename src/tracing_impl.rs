@@ -0,0 +1,139 @@
+//! Module for rendering `tracing` events as logs.
+
+use crate::{level, scope::Scope, Caller, Location, Log, LogLevel};
+use std::fmt::Debug;
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// A `tracing-subscriber` layer that outputs every event as a [`Log`].
+///
+/// Events at levels that are not [recorded](level::recorded) are skipped before their fields
+/// are formatted. This only affects this layer, the other layers of the subscriber still see
+/// them.
+#[derive(Debug)]
+pub struct LogLayer;
+
+/// Collects the fields of a span or event, keeping the `message` field separate.
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.fields
+                .push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{value:?}"));
+        } else {
+            self.fields
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+}
+
+impl<S> Layer<S> for LogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+
+        span.extensions_mut().insert(Scope {
+            name: span.name().to_string(),
+            fields: visitor.fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+
+        if let Some(scope) = span.extensions_mut().get_mut::<Scope>() {
+            scope.fields.extend(visitor.fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+
+        // Filtering in `enabled` or `register_callsite` would disable the event for every layer.
+        if !level::recorded((*metadata.level()).into()) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let message = visitor
+            .message
+            .into_iter()
+            .chain(
+                visitor
+                    .fields
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}")),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut log = Log::new((*metadata.level()).into(), message).target(metadata.target());
+
+        if let Some(path) = metadata.file()
+            && let Some(line) = metadata.line()
+        {
+            log = log.location(Location::from_path_line(path, line as usize));
+
+            if let Some(module_path) = metadata.module_path() {
+                log = log.caller(Caller {
+                    module_path,
+                    file: path,
+                    line,
+                });
+            }
+        }
+
+        if let Some(spans) = ctx.event_scope(event) {
+            for span in spans.from_root() {
+                if let Some(scope) = span.extensions().get::<Scope>() {
+                    log.scopes.push(scope.clone());
+                }
+            }
+        }
+
+        log.output();
+    }
+}
+
+impl From<Level> for LogLevel {
+    fn from(val: Level) -> Self {
+        match val {
+            Level::TRACE => LogLevel::Trace,
+            Level::DEBUG => LogLevel::Debug,
+            Level::INFO => LogLevel::Info,
+            Level::WARN => LogLevel::Warning,
+            Level::ERROR => LogLevel::Error,
+        }
+    }
+}
@@ -1,6 +1,74 @@
 use crate::location::Section;
 use colored::{Color, Colorize};
-use std::ops::RangeInclusive;
+use std::{borrow::Cow, env, fmt, ops::RangeInclusive};
+
+/// Formats the message of a log, without allocating if it has no arguments.
+pub fn message(args: fmt::Arguments) -> Cow<'static, str> {
+    match args.as_str() {
+        Some(message) => Cow::Borrowed(message),
+        None => Cow::Owned(fmt::format(args)),
+    }
+}
+
+/// Name of the program, as set by [`set_app_name!`](crate::set_app_name), or else the name of
+/// its executable.
+pub fn app_name() -> Option<String> {
+    env::var("LOGGER_APP_NAME").ok().or_else(|| {
+        env::current_exe()
+            .ok()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    })
+}
+
+/// Removes the color codes from rendered output.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/// Converts a byte column of `line` into a column in UTF-16 code units, as counted by LSP and
+/// SARIF.
+pub fn utf16_column(line: &str, col: usize) -> usize {
+    let mut col = col.min(line.len());
+    while !line.is_char_boundary(col) {
+        col -= 1;
+    }
+
+    line[..col].encode_utf16().count()
+}
+
+/// Converts days since the Unix epoch into a year, month and day, see
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+pub fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
 
 pub fn thing(
     input: impl Into<String>,
@@ -82,3 +150,17 @@ pub fn remove_excess_tabs(input: impl Into<String>) -> String {
         .collect::<Vec<String>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_date_from_days() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(10_956), (1999, 12, 31));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(19_844), (2024, 5, 1));
+        assert_eq!(civil_date(47_541), (2100, 3, 1));
+    }
+}